@@ -0,0 +1,171 @@
+use crate::inventory::InventoryEntry;
+use crate::resource_record::SRV;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::net::{IpAddr, SocketAddr};
+
+pub fn order_addresses(addresses: &[IpAddr], port: u16) -> Vec<SocketAddr> {
+  let unique = addresses
+    .iter()
+    .enumerate()
+    .filter(|(index, a)| !addresses[..*index].contains(a))
+    .map(|(_, a)| *a);
+  let (v6, v4): (Vec<IpAddr>, Vec<IpAddr>) = unique.partition(|a| a.is_ipv6());
+  let (preferred, other) = if v6.is_empty() { (v4, v6) } else { (v6, v4) };
+
+  let mut ordered = vec![];
+  let mut preferred = preferred.into_iter();
+  let mut other = other.into_iter();
+  loop {
+    match (preferred.next(), other.next()) {
+      (None, None) => break,
+      (first, second) => ordered.extend(first.into_iter().chain(second)),
+    }
+  }
+
+  ordered
+    .into_iter()
+    .map(|a| SocketAddr::new(a, port))
+    .collect()
+}
+
+fn random(max: u32) -> u32 {
+  let mut hasher = RandomState::new().build_hasher();
+  hasher.write_u32(max);
+  (hasher.finish() % (max as u64 + 1)) as u32
+}
+
+pub fn order_srv_targets(records: &[SRV]) -> Vec<SRV> {
+  order_srv_targets_with(records, random)
+}
+
+pub fn order_srv_targets_with<R>(records: &[SRV], mut random: R) -> Vec<SRV>
+where
+  R: FnMut(u32) -> u32,
+{
+  let mut remaining = records
+    .iter()
+    .filter(|r| !r.target.is_empty() && r.target != ".")
+    .cloned()
+    .collect::<Vec<SRV>>();
+  remaining.sort_by_key(|r| (r.priority, r.weight != 0));
+
+  let mut ordered = vec![];
+  while let Some(priority) = remaining.first().map(|r| r.priority) {
+    let mut group = remaining
+      .iter()
+      .filter(|r| r.priority == priority)
+      .cloned()
+      .collect::<Vec<SRV>>();
+    remaining.retain(|r| r.priority != priority);
+
+    while !group.is_empty() {
+      let total = group.iter().fold(0, |sum, r| sum + r.weight as u32);
+      let selected = random(total);
+      let mut running = 0;
+      let index = group
+        .iter()
+        .position(|r| {
+          running += r.weight as u32;
+          running >= selected
+        })
+        .unwrap_or(0);
+      ordered.push(group.remove(index));
+    }
+  }
+
+  ordered
+}
+
+impl InventoryEntry {
+  pub fn socket_addresses(&self) -> Vec<SocketAddr> {
+    match self.port {
+      Some(port) => order_addresses(&self.addresses, port),
+      None => vec![],
+    }
+  }
+}
+
+mod test {
+
+  #[test]
+  fn order_addresses() {
+    let addresses = vec![
+      "192.168.1.10".parse().unwrap(),
+      "192.168.1.11".parse().unwrap(),
+      "192.168.1.12".parse().unwrap(),
+      "fd00::10".parse().unwrap(),
+      "192.168.1.10".parse().unwrap(),
+    ];
+    let ordered = super::order_addresses(&addresses, 8009)
+      .iter()
+      .map(|a| a.to_string())
+      .collect::<Vec<String>>();
+    assert_eq!(
+      vec![
+        "[fd00::10]:8009",
+        "192.168.1.10:8009",
+        "192.168.1.11:8009",
+        "192.168.1.12:8009"
+      ],
+      ordered
+    );
+  }
+
+  #[allow(dead_code)]
+  fn srv(priority: u16, weight: u16, target: &str) -> crate::resource_record::SRV {
+    crate::resource_record::SRV {
+      priority,
+      weight,
+      port: 8009,
+      target: target.to_owned(),
+    }
+  }
+
+  #[test]
+  fn order_srv_targets_with() {
+    let records = [
+      srv(20, 0, "backup.local"),
+      srv(10, 60, "a.local"),
+      srv(10, 40, "b.local"),
+      srv(10, 0, "c.local"),
+    ];
+    let targets = |selections: &[u32]| {
+      let mut selections = selections.iter();
+      super::order_srv_targets_with(&records, |_| *selections.next().unwrap())
+        .into_iter()
+        .map(|r| r.target)
+        .collect::<Vec<String>>()
+    };
+    assert_eq!(
+      vec!["b.local", "a.local", "c.local", "backup.local"],
+      targets(&[61, 50, 0, 0])
+    );
+    assert_eq!(
+      vec!["c.local", "a.local", "b.local", "backup.local"],
+      targets(&[0, 1, 0, 0])
+    );
+  }
+
+  #[test]
+  fn order_srv_targets() {
+    let records = [srv(0, 0, "."), srv(0, 5, "a.local"), srv(1, 5, "b.local")];
+    let ordered = super::order_srv_targets(&records);
+    assert_eq!(
+      vec!["a.local", "b.local"],
+      ordered
+        .iter()
+        .map(|r| r.target.as_str())
+        .collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn order_addresses_with_single_family() {
+    let addresses = vec!["192.168.1.10".parse().unwrap()];
+    assert_eq!(
+      vec!["192.168.1.10:80".parse::<std::net::SocketAddr>().unwrap()],
+      super::order_addresses(&addresses, 80)
+    );
+  }
+}
@@ -0,0 +1,276 @@
+use crate::message::{parse_with_options, Message};
+use crate::query::{QClass, QType, Query, QuestionResponseType};
+use crate::resource_record::ResourceRecord;
+use crate::shared::{ErrorCode, Label, ParseError, ParseOptions};
+use std::ops::Range;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Annotation {
+  pub range: Range<usize>,
+  pub field_path: String,
+  pub description: String,
+}
+
+fn annotation(range: Range<usize>, field_path: String, description: String) -> Annotation {
+  Annotation {
+    range,
+    field_path,
+    description,
+  }
+}
+
+fn annotate_header(message: &Message) -> Vec<Annotation> {
+  let header = &message.header;
+  vec![
+    annotation(0..2, "header.id".to_owned(), format!("{}", header.id)),
+    annotation(
+      2..4,
+      "header.flags".to_owned(),
+      format!(
+        "{:?}, {:?}, {:?}, {:?}, {:?}, {:?}, z={}, {:?}",
+        header.query_or_response,
+        header.operation_code,
+        header.authoritative_answer,
+        header.truncation,
+        header.recursion_desired,
+        header.recursion_available,
+        header.z,
+        header.response_code
+      ),
+    ),
+    annotation(
+      4..6,
+      "header.question_count".to_owned(),
+      format!("{}", header.question_count),
+    ),
+    annotation(
+      6..8,
+      "header.answer_count".to_owned(),
+      format!("{}", header.answer_count),
+    ),
+    annotation(
+      8..10,
+      "header.name_server_count".to_owned(),
+      format!("{}", header.name_server_count),
+    ),
+    annotation(
+      10..12,
+      "header.additional_count".to_owned(),
+      format!("{}", header.additional_count),
+    ),
+  ]
+}
+
+fn annotate_name(path: &str, labels: &[Label]) -> Vec<Annotation> {
+  labels
+    .iter()
+    .enumerate()
+    .map(|(index, label)| {
+      let start = label.offset() as usize;
+      let description = match label {
+        Label::Value(_, Some(data)) => format!("label \"{}\"", String::from_utf8_lossy(data)),
+        Label::Value(_, None) => "root label".to_owned(),
+        Label::Pointer(_, pointer) => format!("pointer to offset {}", pointer),
+      };
+      annotation(
+        start..start + label.size(),
+        format!("{}.name.labels[{}]", path, index),
+        description,
+      )
+    })
+    .collect()
+}
+
+fn name_end(labels: &[Label]) -> usize {
+  labels
+    .last()
+    .map(|l| l.offset() as usize + l.size())
+    .unwrap_or(0)
+}
+
+fn describe_query_type(query: &Query) -> String {
+  match query.q_type() {
+    QType::Type(t) => format!("{:?}", t),
+    q_type => format!("{:?}", q_type),
+  }
+}
+
+fn describe_query_class(query: &Query) -> String {
+  let class = match query.q_class() {
+    QClass::Class(c) => format!("{:?}", c),
+    QClass::Any => "Any".to_owned(),
+  };
+  match query.q_response_type() {
+    QuestionResponseType::QU => format!("{}, unicast response", class),
+    QuestionResponseType::QM => class,
+  }
+}
+
+fn annotate_queries(message: &Message, data: &[u8]) -> Result<Vec<Annotation>, ParseError> {
+  let mut annotations = vec![];
+  for (index, query) in message.queries.iter().enumerate() {
+    let path = format!("queries[{}]", index);
+    let start = name_end(&query.values);
+    if data.get(start..start + 4).is_none() {
+      return Err(ParseError::QueryError(
+        ErrorCode::TruncatedQuery,
+        format!("Data not long enough to annotate {}", path),
+      ));
+    }
+    annotations.extend(annotate_name(&path, &query.values));
+    annotations.push(annotation(
+      start..start + 2,
+      format!("{}.type", path),
+      describe_query_type(query),
+    ));
+    annotations.push(annotation(
+      start + 2..start + 4,
+      format!("{}.class", path),
+      describe_query_class(query),
+    ));
+  }
+  Ok(annotations)
+}
+
+fn annotate_resource_records(section: &str, records: &[ResourceRecord]) -> Vec<Annotation> {
+  let mut annotations = vec![];
+  for (index, record) in records.iter().enumerate() {
+    let path = format!("{}[{}]", section, index);
+    let start = name_end(&record.values);
+    let data_start = start + 10;
+    let data_end = data_start + record.resource_record_data_length as usize;
+    let class = if record.cache_flush {
+      format!("{:?}, cache flush", record.class)
+    } else {
+      format!("{:?}", record.class)
+    };
+
+    annotations.extend(annotate_name(&path, &record.values));
+    annotations.extend(vec![
+      annotation(
+        start..start + 2,
+        format!("{}.type", path),
+        format!("{:?}", record.resource_record_type),
+      ),
+      annotation(start + 2..start + 4, format!("{}.class", path), class),
+      annotation(
+        start + 4..start + 8,
+        format!("{}.ttl", path),
+        format!("{}", record.ttl),
+      ),
+      annotation(
+        start + 8..start + 10,
+        format!("{}.data_length", path),
+        format!("{}", record.resource_record_data_length),
+      ),
+      annotation(
+        data_start..data_end,
+        format!("{}.data", path),
+        format!("{:?}", record.resource_record_data),
+      ),
+    ]);
+  }
+  annotations
+}
+
+pub fn annotate(message: &Message, data: &[u8]) -> Result<Vec<Annotation>, ParseError> {
+  let mut annotations = annotate_header(message);
+  annotations.extend(annotate_queries(message, data)?);
+  annotations.extend(annotate_resource_records("answers", &message.answers));
+  annotations.extend(annotate_resource_records(
+    "name_servers",
+    &message.name_servers,
+  ));
+  annotations.extend(annotate_resource_records(
+    "additional_records",
+    &message.additional_records,
+  ));
+
+  let end = annotations.iter().map(|a| a.range.end).max().unwrap_or(0);
+  if end < data.len() {
+    annotations.push(annotation(
+      end..data.len(),
+      "trailing_data".to_owned(),
+      format!("{} bytes", data.len() - end),
+    ));
+  }
+
+  Ok(annotations)
+}
+
+pub fn parse_annotated(
+  data: &[u8],
+  options: &ParseOptions,
+) -> Result<(Message, Vec<Annotation>), ParseError> {
+  let message = parse_with_options(data, options)?;
+  let annotations = annotate(&message, data)?;
+  Ok((message, annotations))
+}
+
+mod test {
+
+  #[test]
+  fn parse_annotated() {
+    let data = [
+      0, 1, 132, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1, 192,
+      12, 0, 1, 128, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 2, 255,
+    ];
+    let options = crate::shared::ParseOptions::from(crate::shared::Profile::PermissiveMdns);
+    let (_, annotations) = super::parse_annotated(&data, &options).unwrap();
+
+    let paths = annotations
+      .iter()
+      .map(|a| (a.range.clone(), a.field_path.as_str()))
+      .collect::<Vec<(std::ops::Range<usize>, &str)>>();
+    assert_eq!(
+      vec![
+        (0..2, "header.id"),
+        (2..4, "header.flags"),
+        (4..6, "header.question_count"),
+        (6..8, "header.answer_count"),
+        (8..10, "header.name_server_count"),
+        (10..12, "header.additional_count"),
+        (12..14, "queries[0].name.labels[0]"),
+        (14..20, "queries[0].name.labels[1]"),
+        (20..21, "queries[0].name.labels[2]"),
+        (21..23, "queries[0].type"),
+        (23..25, "queries[0].class"),
+        (25..27, "answers[0].name.labels[0]"),
+        (27..29, "answers[0].type"),
+        (29..31, "answers[0].class"),
+        (31..35, "answers[0].ttl"),
+        (35..37, "answers[0].data_length"),
+        (37..41, "answers[0].data"),
+        (41..42, "trailing_data"),
+      ],
+      paths
+    );
+    assert_eq!("A", annotations[9].description);
+    assert_eq!("IN", annotations[10].description);
+    assert_eq!("pointer to offset 12", annotations[11].description);
+    assert_eq!("IN, cache flush", annotations[13].description);
+  }
+
+  #[test]
+  fn annotate_queries_with_unicast_response() {
+    let data = [
+      0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 255, 128, 255,
+    ];
+    let options = crate::shared::ParseOptions::from(crate::shared::Profile::PermissiveMdns);
+    let (_, annotations) = super::parse_annotated(&data, &options).unwrap();
+    assert_eq!("Any", annotations[9].description);
+    assert_eq!("Any, unicast response", annotations[10].description);
+  }
+
+  #[test]
+  fn annotate_with_short_data() {
+    let data = [
+      0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1,
+    ];
+    let message = crate::message::parse(&data).unwrap();
+    match super::annotate(&message, &data[..23]) {
+      Err(super::ParseError::QueryError(..)) => {}
+      _ => panic!(),
+    }
+  }
+}
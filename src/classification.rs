@@ -0,0 +1,109 @@
+use crate::header::{AuthoritativeAnswer, ResponseCode};
+use crate::message::Message;
+use crate::resource_record::ResourceRecordData;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Classification {
+  Positive,
+  NxDomain,
+  NoData,
+  Referral,
+  ServFail,
+  Error(ResponseCode),
+}
+
+impl Message {
+  pub fn classification(&self) -> Classification {
+    let authoritative = self.header.authoritative_answer == AuthoritativeAnswer::Authoritative;
+    match &self.header.response_code {
+      ResponseCode::NoError if !self.answers.is_empty() => Classification::Positive,
+      ResponseCode::NoError if !authoritative && self.referral().is_some() => {
+        Classification::Referral
+      }
+      ResponseCode::NoError => Classification::NoData,
+      ResponseCode::NameError => Classification::NxDomain,
+      ResponseCode::ServerFailure => Classification::ServFail,
+      response_code => Classification::Error(response_code.clone()),
+    }
+  }
+
+  pub fn negative_ttl(&self) -> Option<u32> {
+    match self.classification() {
+      Classification::NxDomain | Classification::NoData => {}
+      _ => return None,
+    }
+
+    self
+      .name_servers
+      .iter()
+      .find_map(|r| match &r.resource_record_data {
+        ResourceRecordData::SOA(soa) => Some(r.ttl.min(soa.minimum)),
+        _ => None,
+      })
+  }
+}
+
+#[cfg(test)]
+mod test {
+
+  fn message(flags: [u8; 2], answers: &[&str], name_servers: &[&str]) -> crate::message::Message {
+    let mut message = crate::test_support::message(flags, answers, name_servers, &[]);
+    message.queries.push("example.com A".parse().unwrap());
+    message
+  }
+
+  #[test]
+  fn classification() {
+    let soa = "example.com. 3600 IN SOA ns.example.com. admin.example.com. 1 7200 3600 1209600 300";
+    let test_data = [
+      (
+        message([129, 128], &["example.com. 300 IN A 93.184.216.34"], &[]),
+        super::Classification::Positive,
+        None,
+      ),
+      (
+        message([129, 131], &[], &[soa]),
+        super::Classification::NxDomain,
+        Some(300),
+      ),
+      (
+        message([129, 128], &[], &[soa]),
+        super::Classification::NoData,
+        Some(300),
+      ),
+      (
+        message(
+          [129, 0],
+          &[],
+          &["example.com. 172800 IN NS a.iana-servers.net."],
+        ),
+        super::Classification::Referral,
+        None,
+      ),
+      (
+        message(
+          [133, 0],
+          &[],
+          &["example.com. 172800 IN NS a.iana-servers.net."],
+        ),
+        super::Classification::NoData,
+        None,
+      ),
+      (
+        message([129, 130], &[], &[]),
+        super::Classification::ServFail,
+        None,
+      ),
+      (
+        message([129, 133], &[], &[]),
+        super::Classification::Error(crate::header::ResponseCode::Refused),
+        None,
+      ),
+    ];
+
+    for (message, classification, negative_ttl) in &test_data {
+      assert_eq!(*classification, message.classification());
+      assert_eq!(*negative_ttl, message.negative_ttl());
+    }
+  }
+}
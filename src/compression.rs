@@ -0,0 +1,301 @@
+use crate::message::Message;
+use crate::resource_record::{ResourceRecord, ResourceRecordData};
+use crate::shared::{encode_name, labels_to_name, parse_name, Label, Limits};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct LabelStatistics {
+  pub name_count: usize,
+  pub label_count: usize,
+  pub pointer_count: usize,
+  pub name_bytes: usize,
+  pub optimal_name_bytes: usize,
+  pub wasted_bytes: usize,
+  pub forward_pointers: Vec<(u16, u16)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CompressionPointer {
+  pub offset: u16,
+  pub target: u16,
+  pub name: String,
+  pub suffix: String,
+}
+
+fn name_end(labels: &[Label]) -> usize {
+  labels
+    .last()
+    .map(|l| l.offset() as usize + l.size())
+    .unwrap_or(0)
+}
+
+fn wire_labels(name: &str) -> Vec<Vec<u8>> {
+  let bytes = encode_name(name).unwrap_or_default();
+  let mut labels = vec![];
+  let mut offset = 0;
+  while offset < bytes.len() && bytes[offset] != 0 {
+    let end = (offset + 1 + bytes[offset] as usize).min(bytes.len());
+    labels.push(bytes[offset + 1..end].to_vec());
+    offset = end;
+  }
+  labels
+}
+
+fn parse_names(mut offset: usize, data: &[u8], names: &[&String]) -> Vec<(Vec<Label>, String)> {
+  let mut parsed = vec![];
+  for name in names {
+    match parse_name(offset, data, &Limits::default()) {
+      Ok(labels) => {
+        offset += labels.iter().fold(0, |sum, l| sum + l.size());
+        parsed.push((labels, (*name).clone()));
+      }
+      Err(_) => break,
+    }
+  }
+  parsed
+}
+
+fn rdata_names(record: &ResourceRecord, data: &[u8]) -> Vec<(Vec<Label>, String)> {
+  let data_start = name_end(&record.values) + 10;
+  match &record.resource_record_data {
+    ResourceRecordData::PTR(name) | ResourceRecordData::NS(name) => {
+      parse_names(data_start, data, &[name])
+    }
+    ResourceRecordData::SRV(srv) => parse_names(data_start + 6, data, &[&srv.target]),
+    ResourceRecordData::SOA(soa) => parse_names(data_start, data, &[&soa.mname, &soa.rname]),
+    _ => vec![],
+  }
+}
+
+fn names(message: &Message, data: &[u8]) -> Vec<(Vec<Label>, String)> {
+  let mut names = message
+    .queries
+    .iter()
+    .map(|q| (q.values.clone(), q.name.clone()))
+    .collect::<Vec<_>>();
+
+  message
+    .answers
+    .iter()
+    .chain(message.name_servers.iter())
+    .chain(message.additional_records.iter())
+    .for_each(|record| {
+      names.push((record.values.clone(), record.name.clone()));
+      names.extend(rdata_names(record, data));
+    });
+
+  names
+}
+
+fn optimal_size(seen: &mut Vec<Vec<Vec<u8>>>, labels: &[Vec<u8>]) -> usize {
+  let shared = (0..labels.len())
+    .find(|index| seen.iter().any(|s| s.as_slice() == &labels[*index..]))
+    .unwrap_or(labels.len());
+
+  for index in 0..shared {
+    seen.push(labels[index..].to_vec());
+  }
+
+  let prefix = labels[..shared].iter().fold(0, |sum, l| sum + l.len() + 1);
+  if shared < labels.len() {
+    prefix + 2
+  } else {
+    prefix + 1
+  }
+}
+
+fn expand_name(offset: u16, data: &[u8]) -> Option<String> {
+  let mut labels = vec![];
+  let mut visited = vec![];
+  let mut next = Some(offset);
+
+  while let Some(offset) = next.take() {
+    if visited.contains(&offset) {
+      return None;
+    }
+    visited.push(offset);
+    for label in parse_name(offset as usize, data, &Limits::default()).ok()? {
+      match label {
+        Label::Value(_, Some(_)) => labels.push(label),
+        Label::Pointer(_, target) => next = Some(target),
+        Label::Value(_, None) => {}
+      }
+    }
+  }
+
+  Some(labels_to_name(&labels))
+}
+
+pub fn compression_pointers(message: &Message, data: &[u8]) -> Vec<CompressionPointer> {
+  names(message, data)
+    .into_iter()
+    .flat_map(|(labels, name)| {
+      labels
+        .into_iter()
+        .filter_map(move |label| match label {
+          Label::Pointer(offset, target) => Some(CompressionPointer {
+            offset,
+            target,
+            name: name.clone(),
+            suffix: expand_name(target, data).unwrap_or_default(),
+          }),
+          _ => None,
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect()
+}
+
+pub fn label_statistics(message: &Message, data: &[u8]) -> LabelStatistics {
+  let names = names(message, data);
+  let mut seen = vec![];
+
+  let labels = names.iter().flat_map(|(labels, _)| labels.iter());
+  let forward_pointers = labels
+    .clone()
+    .filter_map(|label| match label {
+      Label::Pointer(offset, target) if *target >= *offset => Some((*offset, *target)),
+      _ => None,
+    })
+    .collect();
+  let name_bytes = labels.clone().fold(0, |sum, l| sum + l.size());
+  let optimal_name_bytes = names.iter().fold(0, |sum, (_, name)| {
+    sum + optimal_size(&mut seen, &wire_labels(name))
+  });
+
+  LabelStatistics {
+    name_count: names.len(),
+    label_count: labels
+      .clone()
+      .filter(|l| matches!(l, Label::Value(_, Some(_))))
+      .count(),
+    pointer_count: labels.filter(|l| matches!(l, Label::Pointer(_, _))).count(),
+    name_bytes,
+    optimal_name_bytes,
+    wasted_bytes: name_bytes.saturating_sub(optimal_name_bytes),
+    forward_pointers,
+  }
+}
+
+mod test {
+
+  #[allow(dead_code)]
+  fn soa_response() -> Vec<u8> {
+    vec![
+      0, 0, 132, 0, 0, 0, 0, 0, 0, 1, 0, 0, // header
+      7, 101, 120, 97, 109, 112, 108, 101, 3, 99, 111, 109, 0, 0, 6, 0, 1, 0, 0, 14, 16, 0,
+      33, // example.com SOA
+      2, 110, 115, 192, 12, // ns.example.com
+      5, 97, 100, 109, 105, 110, 192, 12, // admin.example.com
+      0, 0, 0, 1, 0, 0, 28, 32, 0, 0, 14, 16, 0, 18, 117, 0, 0, 0, 1, 44,
+    ]
+  }
+
+  #[test]
+  fn label_statistics() {
+    let data = [
+      0, 0, 132, 0, 0, 0, 0, 2, 0, 0, 0, 0, // header
+      1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 2, 1, 98, 5,
+      108, 111, 99, 97, 108, 0, 0, 1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 3,
+    ];
+    let message = crate::message::parse(&data).unwrap();
+    let statistics = super::label_statistics(&message, &data);
+    assert_eq!(
+      super::LabelStatistics {
+        name_count: 2,
+        label_count: 4,
+        pointer_count: 0,
+        name_bytes: 18,
+        optimal_name_bytes: 13,
+        wasted_bytes: 5,
+        forward_pointers: vec![],
+      },
+      statistics
+    );
+  }
+
+  #[test]
+  fn label_statistics_with_soa() {
+    let data = soa_response();
+    let message = crate::message::parse(&data).unwrap();
+    let statistics = super::label_statistics(&message, &data);
+    assert_eq!(
+      super::LabelStatistics {
+        name_count: 3,
+        label_count: 4,
+        pointer_count: 2,
+        name_bytes: 26,
+        optimal_name_bytes: 26,
+        wasted_bytes: 0,
+        forward_pointers: vec![],
+      },
+      statistics
+    );
+  }
+
+  #[test]
+  fn compression_pointers() {
+    let data = [
+      0, 0, 132, 0, 0, 0, 0, 2, 0, 0, 0, 0, // header
+      4, 95, 104, 97, 112, 4, 95, 116, 99, 112, 5, 108, 111, 99, 97, 108, 0, 0, 12, 0, 1, 0, 0, 17,
+      148, 0, 9, 6, 66, 114, 105, 100, 103, 101, 192, 12, // ptr
+      192, 39, 0, 1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 10, // a
+    ];
+    let message = crate::message::parse(&data).unwrap();
+    assert_eq!(
+      vec![
+        super::CompressionPointer {
+          offset: 46,
+          target: 12,
+          name: "Bridge._hap._tcp.local".to_owned(),
+          suffix: "_hap._tcp.local".to_owned(),
+        },
+        super::CompressionPointer {
+          offset: 48,
+          target: 39,
+          name: "Bridge._hap._tcp.local".to_owned(),
+          suffix: "Bridge._hap._tcp.local".to_owned(),
+        },
+      ],
+      super::compression_pointers(&message, &data)
+    );
+  }
+
+  #[test]
+  fn compression_pointers_with_soa() {
+    let data = soa_response();
+    let message = crate::message::parse(&data).unwrap();
+    assert_eq!(
+      vec![
+        super::CompressionPointer {
+          offset: 38,
+          target: 12,
+          name: "ns.example.com".to_owned(),
+          suffix: "example.com".to_owned(),
+        },
+        super::CompressionPointer {
+          offset: 46,
+          target: 12,
+          name: "admin.example.com".to_owned(),
+          suffix: "example.com".to_owned(),
+        },
+      ],
+      super::compression_pointers(&message, &data)
+    );
+  }
+
+  #[test]
+  fn label_statistics_with_forward_pointer() {
+    let data = [
+      0, 0, 132, 0, 0, 0, 0, 1, 0, 0, 0, 0, // header
+      1, 97, 192, 26, 0, 1, 0, 1, 0, 0, 0, 120, 0, 7, 5, 108, 111, 99, 97, 108, 0,
+    ];
+    let options = crate::shared::ParseOptions {
+      allow_data_length_mismatch: true,
+      ..Default::default()
+    };
+    let message = crate::message::parse_with_options(&data, &options).unwrap();
+    let statistics = super::label_statistics(&message, &data);
+    assert_eq!(vec![(14, 26)], statistics.forward_pointers);
+    assert_eq!(1, statistics.pointer_count);
+  }
+}
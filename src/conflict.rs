@@ -0,0 +1,198 @@
+use crate::header::QueryOrResponse;
+use crate::message::Message;
+use crate::name::Name;
+use crate::resource_record::{RecordType, ResourceRecord, ResourceRecordData};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConflictDetected {
+  pub name: String,
+  pub resource_record_type: RecordType,
+  pub owner: SocketAddr,
+  pub owner_data: Vec<ResourceRecordData>,
+  pub claimant: SocketAddr,
+  pub claimant_data: ResourceRecordData,
+}
+
+type ClaimKey = (Name, RecordType);
+
+#[derive(Default)]
+pub struct ConflictDetector {
+  claims: HashMap<ClaimKey, (SocketAddr, Vec<ResourceRecordData>)>,
+}
+
+fn is_unique_type(resource_record_type: &RecordType) -> bool {
+  matches!(
+    resource_record_type,
+    RecordType::A | RecordType::AAAA | RecordType::SRV
+  )
+}
+
+fn sorted_for_probe(records: &[ResourceRecord]) -> Vec<&ResourceRecord> {
+  let mut records = records.iter().collect::<Vec<_>>();
+  records.sort_by(|a, b| a.cmp_probe(b));
+  records
+}
+
+pub fn probe_tiebreak(ours: &[ResourceRecord], theirs: &[ResourceRecord]) -> Ordering {
+  let (ours, theirs) = (sorted_for_probe(ours), sorted_for_probe(theirs));
+
+  ours
+    .iter()
+    .zip(theirs.iter())
+    .map(|(a, b)| a.cmp_probe(b))
+    .find(|o| *o != Ordering::Equal)
+    .unwrap_or_else(|| ours.len().cmp(&theirs.len()))
+}
+
+impl ConflictDetector {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn observe(&mut self, source: SocketAddr, message: &Message) -> Vec<ConflictDetected> {
+    if message.header.query_or_response != QueryOrResponse::Response {
+      return vec![];
+    }
+
+    let mut conflicts = vec![];
+    let records = message
+      .answers
+      .iter()
+      .chain(message.additional_records.iter())
+      .filter(|r| is_unique_type(&r.resource_record_type));
+
+    for record in records {
+      let key = (Name::new(&record.name), record.resource_record_type.clone());
+
+      if record.ttl == 0 {
+        if let Some((owner, _)) = self.claims.get(&key) {
+          if *owner == source {
+            self.claims.remove(&key);
+          }
+        }
+        continue;
+      }
+
+      let (owner, owner_data) = self.claims.entry(key).or_insert_with(|| (source, vec![]));
+
+      if *owner == source {
+        if !owner_data.contains(&record.resource_record_data) {
+          owner_data.push(record.resource_record_data.clone());
+        }
+      } else if !owner_data.contains(&record.resource_record_data) {
+        conflicts.push(ConflictDetected {
+          name: record.name.clone(),
+          resource_record_type: record.resource_record_type.clone(),
+          owner: *owner,
+          owner_data: owner_data.clone(),
+          claimant: source,
+          claimant_data: record.resource_record_data.clone(),
+        });
+      }
+    }
+
+    conflicts
+  }
+}
+
+#[cfg(test)]
+mod test {
+
+  #[test]
+  fn observe_conflicting_address() {
+    let first: std::net::SocketAddr = "192.168.1.10:5353".parse().unwrap();
+    let second: std::net::SocketAddr = "192.168.1.20:5353".parse().unwrap();
+    let mut detector = super::ConflictDetector::new();
+
+    let conflicts = detector.observe(
+      first,
+      &crate::test_support::response(&[
+        "printer.local. 120 IN A 192.168.1.10",
+        "printer.local. 120 IN PTR other.local.",
+      ]),
+    );
+    assert!(conflicts.is_empty());
+
+    let conflicts = detector.observe(
+      second,
+      &crate::test_support::response(&["Printer.local. 120 IN A 192.168.1.20"]),
+    );
+    assert_eq!(1, conflicts.len());
+    assert_eq!(first, conflicts[0].owner);
+    assert_eq!(second, conflicts[0].claimant);
+    assert_eq!(
+      super::ResourceRecordData::A(std::net::Ipv4Addr::new(192, 168, 1, 20)),
+      conflicts[0].claimant_data
+    );
+  }
+
+  #[test]
+  fn observe_identical_data_from_other_source() {
+    let first: std::net::SocketAddr = "192.168.1.10:5353".parse().unwrap();
+    let second: std::net::SocketAddr = "192.168.1.1:5353".parse().unwrap();
+    let mut detector = super::ConflictDetector::new();
+
+    detector.observe(
+      first,
+      &crate::test_support::response(&["printer.local. 120 IN A 192.168.1.10"]),
+    );
+    let conflicts = detector.observe(
+      second,
+      &crate::test_support::response(&["printer.local. 120 IN A 192.168.1.10"]),
+    );
+    assert!(conflicts.is_empty());
+  }
+
+  #[test]
+  fn observe_after_goodbye() {
+    let first: std::net::SocketAddr = "192.168.1.10:5353".parse().unwrap();
+    let second: std::net::SocketAddr = "192.168.1.20:5353".parse().unwrap();
+    let mut detector = super::ConflictDetector::new();
+
+    detector.observe(
+      first,
+      &crate::test_support::response(&["printer.local. 120 IN A 192.168.1.10"]),
+    );
+    detector.observe(
+      first,
+      &crate::test_support::response(&["printer.local. 0 IN A 192.168.1.10"]),
+    );
+    let conflicts = detector.observe(
+      second,
+      &crate::test_support::response(&["printer.local. 120 IN A 192.168.1.20"]),
+    );
+    assert!(conflicts.is_empty());
+  }
+
+  #[test]
+  fn probe_tiebreak() {
+    let records = |lines: &[&str]| {
+      lines
+        .iter()
+        .map(|l| crate::resource_record::parse_resource_record_line(l).unwrap())
+        .collect::<Vec<_>>()
+    };
+    let ours = records(&["printer.local. 120 IN A 169.254.200.50"]);
+    let theirs = records(&["printer.local. 120 IN A 169.254.99.200"]);
+    assert_eq!(
+      std::cmp::Ordering::Greater,
+      super::probe_tiebreak(&ours, &theirs)
+    );
+
+    let more = records(&[
+      "printer.local. 120 IN A 169.254.200.60",
+      "printer.local. 120 IN A 169.254.200.50",
+    ]);
+    assert_eq!(
+      std::cmp::Ordering::Less,
+      super::probe_tiebreak(&ours, &more)
+    );
+    assert_eq!(
+      std::cmp::Ordering::Equal,
+      super::probe_tiebreak(&more, &more)
+    );
+  }
+}
@@ -0,0 +1,65 @@
+use crate::message::{parse_with_options, Message};
+use crate::shared::{ParseError, ParseOptions};
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transport {
+  Udp,
+  Tcp,
+  Multicast,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RxContext {
+  pub source: SocketAddr,
+  pub interface: Option<String>,
+  pub timestamp: SystemTime,
+  pub transport: Transport,
+}
+
+#[derive(Debug)]
+pub struct ReceivedMessage {
+  pub message: Message,
+  pub context: RxContext,
+}
+
+pub fn parse_with_context(data: &[u8], context: RxContext) -> Result<ReceivedMessage, ParseError> {
+  parse_with_context_and_options(data, context, &ParseOptions::default())
+}
+
+pub fn parse_with_context_and_options(
+  data: &[u8],
+  context: RxContext,
+  options: &ParseOptions,
+) -> Result<ReceivedMessage, ParseError> {
+  Ok(ReceivedMessage {
+    message: parse_with_options(data, options)?,
+    context,
+  })
+}
+
+mod test {
+
+  #[test]
+  fn parse_with_context() {
+    let context = super::RxContext {
+      source: "192.168.1.10:5353".parse().unwrap(),
+      interface: Some("en0".to_owned()),
+      timestamp: std::time::UNIX_EPOCH,
+      transport: super::Transport::Multicast,
+    };
+    let data = [0, 0, 132, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let received = super::parse_with_context(&data, context.clone()).unwrap();
+    assert_eq!(context, received.context);
+    assert_eq!(
+      crate::header::QueryOrResponse::Response,
+      received.message.header.query_or_response
+    );
+
+    match super::parse_with_context(&data[..4], context) {
+      Err(super::ParseError::HeaderError(..)) => {}
+      _ => panic!(),
+    }
+  }
+}
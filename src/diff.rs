@@ -0,0 +1,163 @@
+use crate::message::Message;
+use crate::name::Name;
+use crate::resource_record::{RecordType, ResourceRecordData};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub type Rrsets = BTreeMap<(Name, RecordType), Vec<ResourceRecordData>>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RrsetChange {
+  Added(Vec<ResourceRecordData>),
+  Removed(Vec<ResourceRecordData>),
+  Changed {
+    before: Vec<ResourceRecordData>,
+    after: Vec<ResourceRecordData>,
+  },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RrsetDiff {
+  pub name: String,
+  pub resource_record_type: RecordType,
+  pub change: RrsetChange,
+}
+
+pub fn rrsets<'a, I>(messages: I) -> Rrsets
+where
+  I: IntoIterator<Item = &'a Message>,
+{
+  let mut rrsets = Rrsets::new();
+
+  for message in messages {
+    let mut flushed = BTreeSet::new();
+    let records = message
+      .answers
+      .iter()
+      .chain(message.name_servers.iter())
+      .chain(message.additional_records.iter())
+      .filter(|r| r.resource_record_type != RecordType::OPT);
+
+    for record in records {
+      let key = (Name::new(&record.name), record.resource_record_type.clone());
+
+      if record.ttl == 0 {
+        if let Some(data) = rrsets.get_mut(&key) {
+          data.retain(|d| *d != record.resource_record_data);
+          if data.is_empty() {
+            rrsets.remove(&key);
+          }
+        }
+        continue;
+      }
+
+      let data = rrsets.entry(key.clone()).or_default();
+      if record.cache_flush && flushed.insert(key) {
+        data.clear();
+      }
+      if !data.contains(&record.resource_record_data) {
+        data.push(record.resource_record_data.clone());
+      }
+    }
+  }
+
+  rrsets
+}
+
+fn same_data(before: &[ResourceRecordData], after: &[ResourceRecordData]) -> bool {
+  before.len() == after.len() && before.iter().all(|d| after.contains(d))
+}
+
+pub fn diff_rrsets(before: &Rrsets, after: &Rrsets) -> Vec<RrsetDiff> {
+  let keys = before.keys().chain(after.keys()).collect::<BTreeSet<_>>();
+
+  keys
+    .into_iter()
+    .filter_map(|key| {
+      let change = match (before.get(key), after.get(key)) {
+        (None, Some(after)) => RrsetChange::Added(after.clone()),
+        (Some(before), None) => RrsetChange::Removed(before.clone()),
+        (Some(before), Some(after)) if !same_data(before, after) => RrsetChange::Changed {
+          before: before.clone(),
+          after: after.clone(),
+        },
+        _ => return None,
+      };
+      let (name, _) = after
+        .get_key_value(key)
+        .or_else(|| before.get_key_value(key))
+        .map(|(k, _)| k)?;
+      Some(RrsetDiff {
+        name: name.to_string(),
+        resource_record_type: key.1.clone(),
+        change,
+      })
+    })
+    .collect()
+}
+
+pub fn diff_messages(before: &[Message], after: &[Message]) -> Vec<RrsetDiff> {
+  diff_rrsets(&rrsets(before), &rrsets(after))
+}
+
+#[cfg(test)]
+mod test {
+
+  #[test]
+  fn diff_messages() {
+    let before = vec![crate::test_support::response(&[
+      "printer.local. 120 IN A 192.168.1.10",
+      "_ipp._tcp.local. 4500 IN PTR Printer._ipp._tcp.local.",
+      "tv.local. 120 IN A 192.168.1.30",
+    ])];
+    let after = vec![
+      crate::test_support::response(&[
+        "Printer.local. 120 IN A 192.168.1.11",
+        "_ipp._tcp.local. 4500 IN PTR Printer._ipp._tcp.local.",
+        "tv.local. 120 IN A 192.168.1.30",
+        "speaker.local. 120 IN A 192.168.1.40",
+      ]),
+      crate::test_support::response(&["tv.local. 0 IN A 192.168.1.30"]),
+    ];
+
+    let changes = super::diff_messages(&before, &after)
+      .into_iter()
+      .map(|d| (d.name, d.change))
+      .collect::<Vec<_>>();
+    let a = |address: &str| super::ResourceRecordData::A(address.parse().unwrap());
+    assert_eq!(
+      vec![
+        (
+          "Printer.local".to_owned(),
+          super::RrsetChange::Changed {
+            before: vec![a("192.168.1.10")],
+            after: vec![a("192.168.1.11")],
+          }
+        ),
+        (
+          "speaker.local".to_owned(),
+          super::RrsetChange::Added(vec![a("192.168.1.40")])
+        ),
+        (
+          "tv.local".to_owned(),
+          super::RrsetChange::Removed(vec![a("192.168.1.30")])
+        ),
+      ],
+      changes
+    );
+  }
+
+  #[test]
+  fn rrsets_with_cache_flush() {
+    let mut first = crate::test_support::response(&["printer.local. 120 IN A 192.168.1.10"]);
+    let mut second = crate::test_support::response(&[
+      "printer.local. 120 IN A 192.168.1.11",
+      "printer.local. 120 IN A 192.168.1.12",
+    ]);
+    first.answers[0].cache_flush = true;
+    second.answers.iter_mut().for_each(|r| r.cache_flush = true);
+
+    let rrsets = super::rrsets(&[first, second]);
+    assert_eq!(1, rrsets.len());
+    assert_eq!(2, rrsets.values().next().unwrap().len());
+  }
+}
@@ -0,0 +1,157 @@
+use crate::message::Message;
+use crate::resource_record::{RecordType, ResourceRecordData};
+use crate::shared::{ErrorCode, ParseError};
+
+pub const OWNER_OPTION_CODE: u16 = 4;
+
+pub type HardwareAddress = [u8; 6];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct EdnsOption {
+  pub code: u16,
+  pub data: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct OwnerOption {
+  pub version: u8,
+  pub sequence: u8,
+  pub primary_mac: HardwareAddress,
+  pub wakeup_mac: Option<HardwareAddress>,
+  pub password: Option<Vec<u8>>,
+}
+
+pub fn parse_edns_options(data: &[u8]) -> Result<Vec<EdnsOption>, ParseError> {
+  let mut options = vec![];
+  let mut offset = 0;
+
+  while offset < data.len() {
+    if data.len() < offset + 4 {
+      return Err(ParseError::ResourceRecordError(
+        ErrorCode::TruncatedRecord,
+        "Data would overflow when parsing EDNS option".to_owned(),
+      ));
+    }
+    let code = u16::from_be_bytes([data[offset], data[offset + 1]]);
+    let length = u16::from_be_bytes([data[offset + 2], data[offset + 3]]) as usize;
+    if data.len() < offset + 4 + length {
+      return Err(ParseError::ResourceRecordError(
+        ErrorCode::TruncatedRecord,
+        "Data would overflow when parsing EDNS option".to_owned(),
+      ));
+    }
+    options.push(EdnsOption {
+      code,
+      data: data[offset + 4..offset + 4 + length].to_vec(),
+    });
+    offset += 4 + length;
+  }
+
+  Ok(options)
+}
+
+fn hardware_address(data: &[u8]) -> HardwareAddress {
+  let mut address = [0; 6];
+  address.copy_from_slice(&data[..6]);
+  address
+}
+
+pub fn parse_owner_option(data: &[u8]) -> Result<OwnerOption, ParseError> {
+  let (wakeup_mac, password) = match data.len() {
+    8 => (None, None),
+    14 => (Some(hardware_address(&data[8..])), None),
+    18 | 20 => (
+      Some(hardware_address(&data[8..])),
+      Some(data[14..].to_vec()),
+    ),
+    n => {
+      return Err(ParseError::ResourceRecordError(
+        ErrorCode::DataLengthMismatch,
+        format!("Invalid owner option length: {}", n),
+      ))
+    }
+  };
+
+  Ok(OwnerOption {
+    version: data[0],
+    sequence: data[1],
+    primary_mac: hardware_address(&data[2..]),
+    wakeup_mac,
+    password,
+  })
+}
+
+pub fn owner_option(message: &Message) -> Option<OwnerOption> {
+  message
+    .additional_records
+    .iter()
+    .filter(|r| r.resource_record_type == RecordType::OPT)
+    .filter_map(|r| match &r.resource_record_data {
+      ResourceRecordData::Other(data) => parse_edns_options(data).ok(),
+      _ => None,
+    })
+    .flatten()
+    .filter(|o| o.code == OWNER_OPTION_CODE)
+    .find_map(|o| parse_owner_option(&o.data).ok())
+}
+
+pub fn format_hardware_address(address: &HardwareAddress) -> String {
+  address
+    .iter()
+    .map(|b| format!("{:02x}", b))
+    .collect::<Vec<String>>()
+    .join(":")
+}
+
+pub fn parse_hardware_address(value: &str) -> Option<HardwareAddress> {
+  let octets = value
+    .split([':', '-'])
+    .map(|o| match o.len() {
+      2 => u8::from_str_radix(o, 16).ok(),
+      _ => None,
+    })
+    .collect::<Option<Vec<u8>>>()?;
+
+  match octets.len() {
+    6 => Some(hardware_address(&octets)),
+    _ => None,
+  }
+}
+
+mod test {
+
+  #[test]
+  fn parse_owner_option() {
+    let data = [
+      0, 1, 0, 4, 0, 0, 0, 0, 0, 4, 0, 8, 0, 3, 0x28, 0x37, 0x37, 0x12, 0x34, 0x56,
+    ];
+    let options = super::parse_edns_options(&data).unwrap();
+    assert_eq!(2, options.len());
+    let owner = super::parse_owner_option(&options[1].data).unwrap();
+    assert_eq!(
+      "28:37:37:12:34:56",
+      super::format_hardware_address(&owner.primary_mac)
+    );
+    assert_eq!(
+      (0, 3, None),
+      (owner.version, owner.sequence, owner.wakeup_mac)
+    );
+  }
+
+  #[test]
+  fn parse_owner_option_with_invalid_length() {
+    match super::parse_owner_option(&[0, 0, 1]) {
+      Err(super::ParseError::ResourceRecordError(..)) => {}
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_hardware_address() {
+    assert_eq!(
+      Some([0xaa, 0xbb, 0xcc, 0x0d, 0x0e, 0x0f]),
+      super::parse_hardware_address("AA:BB:CC:0D:0E:0F")
+    );
+    assert_eq!(None, super::parse_hardware_address("AA:BB:CC"));
+  }
+}
@@ -0,0 +1,277 @@
+use crate::inventory::InventoryEntry;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FingerprintRule {
+  pub service_type: Option<String>,
+  pub hostname_contains: Option<String>,
+  pub txt_key: Option<String>,
+  pub txt_value_contains: Option<String>,
+  pub vendor: Option<String>,
+  pub device_type: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DeviceProfile {
+  pub host: String,
+  pub vendor: Option<String>,
+  pub device_type: String,
+  pub model: Option<String>,
+}
+
+pub struct Fingerprinter {
+  rules: Vec<FingerprintRule>,
+}
+
+fn rule(
+  service_type: Option<&str>,
+  hostname_contains: Option<&str>,
+  txt: Option<(&str, &str)>,
+  vendor: Option<&str>,
+  device_type: &str,
+) -> FingerprintRule {
+  FingerprintRule {
+    service_type: service_type.map(str::to_owned),
+    hostname_contains: hostname_contains.map(str::to_owned),
+    txt_key: txt.map(|(key, _)| key.to_owned()),
+    txt_value_contains: txt.map(|(_, value)| value.to_owned()),
+    vendor: vendor.map(str::to_owned),
+    device_type: device_type.to_owned(),
+  }
+}
+
+pub fn default_rules() -> Vec<FingerprintRule> {
+  vec![
+    rule(
+      Some("_googlecast._tcp"),
+      None,
+      Some(("md", "Google Home")),
+      Some("Google"),
+      "Smart speaker",
+    ),
+    rule(
+      Some("_googlecast._tcp"),
+      None,
+      Some(("md", "Chromecast")),
+      Some("Google"),
+      "Media player",
+    ),
+    rule(Some("_googlecast._tcp"), None, None, None, "Cast receiver"),
+    rule(
+      Some("_airplay._tcp"),
+      None,
+      Some(("model", "AppleTV")),
+      Some("Apple"),
+      "Media player",
+    ),
+    rule(None, Some("iPhone"), None, Some("Apple"), "Phone"),
+    rule(None, Some("iPad"), None, Some("Apple"), "Tablet"),
+    rule(None, Some("MacBook"), None, Some("Apple"), "Laptop"),
+    rule(
+      Some("_companion-link._tcp"),
+      None,
+      None,
+      Some("Apple"),
+      "Apple device",
+    ),
+    rule(
+      Some("_sonos._tcp"),
+      None,
+      None,
+      Some("Sonos"),
+      "Smart speaker",
+    ),
+    rule(Some("_hap._tcp"), None, None, None, "HomeKit accessory"),
+    rule(Some("_ipp._tcp"), None, None, None, "Printer"),
+    rule(Some("_printer._tcp"), None, None, None, "Printer"),
+  ]
+}
+
+fn contains_ignore_case(value: &str, pattern: &str) -> bool {
+  value
+    .to_ascii_lowercase()
+    .contains(&pattern.to_ascii_lowercase())
+}
+
+fn txt_value<'a>(entry: &'a InventoryEntry, keys: &[&str]) -> Option<&'a str> {
+  entry
+    .txt
+    .iter()
+    .find(|(key, _)| keys.iter().any(|k| key.eq_ignore_ascii_case(k)))
+    .and_then(|(_, value)| value.as_ref().and_then(|v| v.as_text()))
+}
+
+fn host(entry: &InventoryEntry) -> String {
+  entry.host.clone().unwrap_or_else(|| entry.instance.clone())
+}
+
+impl FingerprintRule {
+  pub fn matches(&self, entry: &InventoryEntry) -> bool {
+    let service_type = self
+      .service_type
+      .as_ref()
+      .map(|t| entry.service_type.eq_ignore_ascii_case(t))
+      .unwrap_or(true);
+    let hostname = self
+      .hostname_contains
+      .as_ref()
+      .map(|h| contains_ignore_case(&host(entry), h) || contains_ignore_case(&entry.instance, h))
+      .unwrap_or(true);
+    let txt = self
+      .txt_key
+      .as_ref()
+      .map(|key| match txt_value(entry, &[key]) {
+        Some(value) => self
+          .txt_value_contains
+          .as_ref()
+          .map(|pattern| contains_ignore_case(value, pattern))
+          .unwrap_or(true),
+        None => {
+          self.txt_value_contains.is_none()
+            && entry.txt.iter().any(|(k, _)| k.eq_ignore_ascii_case(key))
+        }
+      })
+      .unwrap_or(true);
+
+    service_type && hostname && txt
+  }
+}
+
+impl Default for Fingerprinter {
+  fn default() -> Self {
+    Fingerprinter {
+      rules: default_rules(),
+    }
+  }
+}
+
+impl Fingerprinter {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_rules(rules: Vec<FingerprintRule>) -> Self {
+    Fingerprinter { rules }
+  }
+
+  pub fn add_rule(&mut self, rule: FingerprintRule) {
+    self.rules.insert(0, rule);
+  }
+
+  pub fn fingerprint(&self, entries: &[InventoryEntry]) -> Vec<DeviceProfile> {
+    let mut profiles: Vec<(usize, DeviceProfile)> = vec![];
+
+    for entry in entries {
+      let rule_index = match self.rules.iter().position(|r| r.matches(entry)) {
+        Some(index) => index,
+        None => continue,
+      };
+      let rule = &self.rules[rule_index];
+      let profile = DeviceProfile {
+        host: host(entry),
+        vendor: rule.vendor.clone(),
+        device_type: rule.device_type.clone(),
+        model: txt_value(entry, &["md", "model", "am", "ty"]).map(str::to_owned),
+      };
+
+      match profiles
+        .iter_mut()
+        .find(|(_, p)| p.host.eq_ignore_ascii_case(&profile.host))
+      {
+        Some((index, existing)) if rule_index < *index => {
+          *index = rule_index;
+          *existing = profile;
+        }
+        Some(_) => {}
+        None => profiles.push((rule_index, profile)),
+      }
+    }
+
+    profiles.into_iter().map(|(_, profile)| profile).collect()
+  }
+}
+
+mod test {
+
+  #[allow(dead_code)]
+  fn entry(
+    instance: &str,
+    service_type: &str,
+    host: &str,
+    txt: &[(&str, &str)],
+  ) -> crate::inventory::InventoryEntry {
+    crate::inventory::InventoryEntry {
+      instance: instance.to_owned(),
+      service_type: service_type.to_owned(),
+      host: Some(host.to_owned()),
+      port: None,
+      addresses: vec![],
+      hardware_address: None,
+      txt: txt
+        .iter()
+        .map(|(k, v)| {
+          (
+            k.to_string(),
+            Some(crate::txt::TxtValue::Text(v.to_string())),
+          )
+        })
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn fingerprint() {
+    let entries = vec![
+      entry(
+        "Kitchen",
+        "_googlecast._tcp",
+        "e0719ee5.local",
+        &[("md", "Google Home Mini")],
+      ),
+      entry(
+        "Johns-iPhone",
+        "_companion-link._tcp",
+        "Johns-iPhone.local",
+        &[],
+      ),
+      entry("Unknown", "_http._tcp", "box.local", &[]),
+    ];
+    let profiles = super::Fingerprinter::new().fingerprint(&entries);
+    assert_eq!(
+      vec![
+        super::DeviceProfile {
+          host: "e0719ee5.local".to_owned(),
+          vendor: Some("Google".to_owned()),
+          device_type: "Smart speaker".to_owned(),
+          model: Some("Google Home Mini".to_owned()),
+        },
+        super::DeviceProfile {
+          host: "Johns-iPhone.local".to_owned(),
+          vendor: Some("Apple".to_owned()),
+          device_type: "Phone".to_owned(),
+          model: None,
+        },
+      ],
+      profiles
+    );
+  }
+
+  #[test]
+  fn fingerprint_with_custom_rule() {
+    let mut fingerprinter = super::Fingerprinter::new();
+    fingerprinter.add_rule(super::FingerprintRule {
+      service_type: Some("_http._tcp".to_owned()),
+      hostname_contains: Some("shelly".to_owned()),
+      vendor: Some("Shelly".to_owned()),
+      device_type: "Relay".to_owned(),
+      ..Default::default()
+    });
+    let profiles = fingerprinter.fingerprint(&[entry(
+      "shelly1-98CD",
+      "_http._tcp",
+      "shelly1-98CD.local",
+      &[],
+    )]);
+    assert_eq!(Some("Shelly".to_owned()), profiles[0].vendor);
+    assert_eq!("Relay", profiles[0].device_type);
+  }
+}
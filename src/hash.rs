@@ -0,0 +1,245 @@
+use crate::message::Message;
+use crate::name::Name;
+use crate::query::QuestionResponseType;
+use crate::resource_record::{RecordType, ResourceRecord, ResourceRecordData};
+
+pub type Digest = [u8; 32];
+
+const K: [u32; 64] = [
+  0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+  0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+  0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+  0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+  0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+  0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+  0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+  0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+pub fn sha256(data: &[u8]) -> Digest {
+  let mut state: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+  ];
+
+  let mut padded = data.to_vec();
+  padded.push(0x80);
+  while padded.len() % 64 != 56 {
+    padded.push(0);
+  }
+  padded.extend(&((data.len() as u64) * 8).to_be_bytes());
+
+  for block in padded.chunks(64) {
+    let mut w = [0u32; 64];
+    for i in 0..16 {
+      w[i] = u32::from_be_bytes([
+        block[i * 4],
+        block[i * 4 + 1],
+        block[i * 4 + 2],
+        block[i * 4 + 3],
+      ]);
+    }
+    for i in 16..64 {
+      let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+      let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+      w[i] = w[i - 16]
+        .wrapping_add(s0)
+        .wrapping_add(w[i - 7])
+        .wrapping_add(s1);
+    }
+
+    let mut v = state;
+    for i in 0..64 {
+      let s1 = v[4].rotate_right(6) ^ v[4].rotate_right(11) ^ v[4].rotate_right(25);
+      let ch = (v[4] & v[5]) ^ (!v[4] & v[6]);
+      let t1 = v[7]
+        .wrapping_add(s1)
+        .wrapping_add(ch)
+        .wrapping_add(K[i])
+        .wrapping_add(w[i]);
+      let s0 = v[0].rotate_right(2) ^ v[0].rotate_right(13) ^ v[0].rotate_right(22);
+      let maj = (v[0] & v[1]) ^ (v[0] & v[2]) ^ (v[1] & v[2]);
+      let t2 = s0.wrapping_add(maj);
+      v = [
+        t1.wrapping_add(t2),
+        v[0],
+        v[1],
+        v[2],
+        v[3].wrapping_add(t1),
+        v[4],
+        v[5],
+        v[6],
+      ];
+    }
+    for (s, v) in state.iter_mut().zip(v.iter()) {
+      *s = s.wrapping_add(*v);
+    }
+  }
+
+  let mut digest = [0; 32];
+  for (chunk, s) in digest.chunks_mut(4).zip(state.iter()) {
+    chunk.copy_from_slice(&s.to_be_bytes());
+  }
+  digest
+}
+
+pub fn digest_to_hex(digest: &Digest) -> String {
+  digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn canonical_name(name: &str) -> Vec<u8> {
+  Name::new(name).canonical_bytes(true)
+}
+
+fn push_data(bytes: &mut Vec<u8>, data: &[u8]) {
+  bytes.extend(&(data.len() as u16).to_be_bytes());
+  bytes.extend(data);
+}
+
+pub fn rrset_hash(
+  name: &Name,
+  resource_record_type: &RecordType,
+  data: &[ResourceRecordData],
+) -> Digest {
+  let mut rdata = data
+    .iter()
+    .map(|d| d.canonical_bytes())
+    .collect::<Vec<Vec<u8>>>();
+  rdata.sort();
+  rdata.dedup();
+
+  let mut bytes = name.canonical_bytes(true);
+  bytes.extend(&resource_record_type.code().to_be_bytes());
+  rdata.iter().for_each(|d| push_data(&mut bytes, d));
+  sha256(&bytes)
+}
+
+fn raw_class(code: u16, top_bit: bool) -> u16 {
+  code | (top_bit as u16) << 15
+}
+
+fn push_resource_record(bytes: &mut Vec<u8>, record: &ResourceRecord) {
+  bytes.extend(canonical_name(&record.name));
+  bytes.extend(&record.resource_record_type.code().to_be_bytes());
+  bytes.extend(&raw_class(record.class.code(), record.cache_flush).to_be_bytes());
+  bytes.extend(&record.ttl.to_be_bytes());
+  push_data(bytes, &record.resource_record_data.canonical_bytes());
+}
+
+pub fn message_hash(message: &Message) -> Digest {
+  let mut message = message.clone();
+  message.normalize();
+
+  let header = &message.header;
+  let mut bytes = vec![];
+  bytes.extend(&header.id.to_be_bytes());
+  bytes.extend(&[
+    (header.query_or_response == crate::header::QueryOrResponse::Response) as u8,
+    header.operation_code_value,
+    (header.authoritative_answer == crate::header::AuthoritativeAnswer::Authoritative) as u8,
+    (header.truncation == crate::header::Truncation::Truncated) as u8,
+    (header.recursion_desired == crate::header::RecursionDesired::RecursionDesired) as u8,
+    (header.recursion_available == crate::header::RA::RecursionAvailable) as u8,
+    header.response_code_value,
+  ]);
+
+  for query in &message.queries {
+    let (q_type, q_class) = query.type_and_class_codes();
+    let unicast = *query.q_response_type() == QuestionResponseType::QU;
+    bytes.extend(canonical_name(&query.name));
+    bytes.extend(&q_type.to_be_bytes());
+    bytes.extend(&raw_class(q_class, unicast).to_be_bytes());
+  }
+
+  let sections = [
+    &message.answers,
+    &message.name_servers,
+    &message.additional_records,
+  ];
+  for (index, records) in sections.iter().enumerate() {
+    bytes.push(index as u8);
+    bytes.extend(&(records.len() as u16).to_be_bytes());
+    records
+      .iter()
+      .for_each(|r| push_resource_record(&mut bytes, r));
+  }
+
+  sha256(&bytes)
+}
+
+mod test {
+
+  #[test]
+  fn sha256() {
+    assert_eq!(
+      "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+      super::digest_to_hex(&super::sha256(b""))
+    );
+    assert_eq!(
+      "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+      super::digest_to_hex(&super::sha256(
+        b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+      ))
+    );
+  }
+
+  #[test]
+  fn rrset_hash_ignores_case_and_order() {
+    let a = |address: &str| super::ResourceRecordData::A(address.parse().unwrap());
+    let first = super::rrset_hash(
+      &crate::name::Name::new("printer.local"),
+      &super::RecordType::A,
+      &[a("192.168.1.10"), a("192.168.1.11")],
+    );
+    let second = super::rrset_hash(
+      &crate::name::Name::new("Printer.LOCAL"),
+      &super::RecordType::A,
+      &[a("192.168.1.11"), a("192.168.1.10")],
+    );
+    let third = super::rrset_hash(
+      &crate::name::Name::new("printer.local"),
+      &super::RecordType::A,
+      &[a("192.168.1.10")],
+    );
+    assert_eq!(first, second);
+    assert_ne!(first, third);
+  }
+
+  #[test]
+  fn message_hash() {
+    let data = [
+      0, 1, 132, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1, 192,
+      12, 0, 1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 2,
+    ];
+    let mut changed = data;
+    changed[40] = 3;
+
+    let hash = super::message_hash(&crate::message::parse(&data).unwrap());
+    assert_eq!(
+      hash,
+      super::message_hash(&crate::message::parse(&data).unwrap())
+    );
+    assert_ne!(
+      hash,
+      super::message_hash(&crate::message::parse(&changed).unwrap())
+    );
+  }
+
+  #[test]
+  fn message_hash_is_canonical() {
+    let data = [
+      0, 1, 132, 0, 0, 0, 0, 2, 0, 0, 0, 0, 1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 128, 1, 0, 0,
+      0, 120, 0, 4, 192, 168, 1, 2, 192, 12, 0, 1, 128, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 3,
+    ];
+    let mut reordered = data;
+    reordered[13] = 65;
+    reordered[34] = 3;
+    reordered[50] = 2;
+    let mut without_flush = data;
+    without_flush[23] = 0;
+    without_flush[39] = 0;
+
+    let hash = |data: &[u8]| super::message_hash(&crate::message::parse(data).unwrap());
+    assert_eq!(hash(&data), hash(&reordered));
+    assert_ne!(hash(&data), hash(&without_flush));
+  }
+}
@@ -1,4 +1,4 @@
-use crate::shared::ParseError;
+use crate::shared::{ErrorCode, ParseError};
 
 const HEADER_SIZE: usize = 12;
 
@@ -6,7 +6,7 @@ type RawHeader = [u8; HEADER_SIZE];
 
 pub type MessageId = u16;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ResponseCode {
   NoError,
   FormatError,
@@ -17,37 +17,37 @@ pub enum ResponseCode {
   Other,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RecursionDesired {
   RecursionDesired,
   RecursionNotDesired,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum QueryOrResponse {
   Query,
   Response,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RA {
   RecursionAvailable,
   RecursionNotAvailable,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Truncation {
   NotTruncated,
   Truncated,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum AuthoritativeAnswer {
   NotAuthoritative,
   Authoritative,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum OperationCode {
   Query,
   InverseQuery,
@@ -55,7 +55,7 @@ pub enum OperationCode {
   Other,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Header {
   pub id: MessageId,
   pub query_or_response: QueryOrResponse,
@@ -76,9 +76,10 @@ pub struct Header {
 
 pub fn create_raw_header(data: &[u8]) -> Result<RawHeader, ParseError> {
   if data.len() < HEADER_SIZE {
-    return Err(ParseError::HeaderError(String::from(
-      "Data is smaller than header",
-    )));
+    return Err(ParseError::HeaderError(
+      ErrorCode::TruncatedHeader,
+      String::from("Data is smaller than header"),
+    ));
   }
 
   let mut header: [u8; HEADER_SIZE] = [0; HEADER_SIZE];
@@ -87,6 +88,12 @@ pub fn create_raw_header(data: &[u8]) -> Result<RawHeader, ParseError> {
   Ok(header)
 }
 
+impl Header {
+  pub fn parse(data: &[u8]) -> Result<Header, ParseError> {
+    parse_header(data)
+  }
+}
+
 pub fn parse_header(data: &[u8]) -> Result<Header, ParseError> {
   let header = create_raw_header(data)?;
 
@@ -215,38 +222,6 @@ fn parse_header_truncated(header: RawHeader) -> Truncation {
 
 mod test {
 
-  #[allow(dead_code)]
-  const DATA_1: [u8; 383] = [
-    0, 2, 132, 0, 0, 0, 0, 1, 0, 0, 0, 3, 11, 95, 103, 111, 111, 103, 108, 101, 99, 97, 115, 116,
-    4, 95, 116, 99, 112, 5, 108, 111, 99, 97, 108, 0, 0, 12, 0, 1, 0, 0, 0, 120, 0, 52, 49, 71,
-    111, 111, 103, 108, 101, 45, 72, 111, 109, 101, 45, 77, 105, 110, 105, 45, 101, 48, 55, 49, 57,
-    101, 101, 53, 100, 55, 102, 56, 57, 98, 102, 100, 57, 101, 97, 55, 52, 52, 53, 97, 55, 49, 48,
-    48, 53, 55, 53, 50, 192, 12, 192, 46, 0, 16, 128, 1, 0, 0, 17, 148, 0, 200, 35, 105, 100, 61,
-    101, 48, 55, 49, 57, 101, 101, 53, 100, 55, 102, 56, 57, 98, 102, 100, 57, 101, 97, 55, 52, 52,
-    53, 97, 55, 49, 48, 48, 53, 55, 53, 50, 35, 99, 100, 61, 69, 48, 48, 53, 52, 69, 50, 53, 48,
-    68, 54, 67, 68, 49, 52, 56, 55, 56, 67, 57, 51, 67, 67, 49, 70, 55, 65, 67, 54, 52, 55, 68, 19,
-    114, 109, 61, 52, 49, 55, 55, 50, 65, 55, 66, 56, 56, 54, 51, 70, 66, 48, 69, 5, 118, 101, 61,
-    48, 53, 19, 109, 100, 61, 71, 111, 111, 103, 108, 101, 32, 72, 111, 109, 101, 32, 77, 105, 110,
-    105, 18, 105, 99, 61, 47, 115, 101, 116, 117, 112, 47, 105, 99, 111, 110, 46, 112, 110, 103,
-    22, 102, 110, 61, 76, 105, 118, 105, 110, 103, 32, 82, 111, 111, 109, 32, 115, 112, 101, 97,
-    107, 101, 114, 9, 99, 97, 61, 49, 57, 56, 54, 54, 48, 4, 115, 116, 61, 48, 15, 98, 115, 61, 70,
-    65, 56, 70, 67, 65, 57, 68, 66, 67, 69, 70, 4, 110, 102, 61, 49, 3, 114, 115, 61, 192, 46, 0,
-    33, 128, 1, 0, 0, 0, 120, 0, 45, 0, 0, 0, 0, 31, 73, 36, 101, 48, 55, 49, 57, 101, 101, 53, 45,
-    100, 55, 102, 56, 45, 57, 98, 102, 100, 45, 57, 101, 97, 55, 45, 52, 52, 53, 97, 55, 49, 48,
-    48, 53, 55, 53, 50, 192, 29, 193, 72, 0, 1, 128, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 137,
-  ];
-
-  #[allow(dead_code)]
-  const DATA_2: [u8; 154] = [
-    0, 0, 0, 0, 0, 3, 0, 2, 0, 0, 0, 1, 8, 95, 104, 111, 109, 101, 107, 105, 116, 4, 95, 116, 99,
-    112, 5, 108, 111, 99, 97, 108, 0, 0, 12, 0, 1, 15, 95, 99, 111, 109, 112, 97, 110, 105, 111,
-    110, 45, 108, 105, 110, 107, 192, 21, 0, 12, 0, 1, 12, 95, 115, 108, 101, 101, 112, 45, 112,
-    114, 111, 120, 121, 4, 95, 117, 100, 112, 192, 26, 0, 12, 0, 1, 192, 37, 0, 12, 0, 1, 0, 0, 17,
-    136, 0, 7, 4, 99, 111, 110, 102, 192, 37, 192, 37, 0, 12, 0, 1, 0, 0, 17, 136, 0, 11, 8, 77,
-    97, 99, 98, 111, 111, 107, 49, 192, 37, 0, 0, 41, 5, 160, 0, 0, 17, 148, 0, 18, 0, 4, 0, 14, 0,
-    105, 118, 66, 139, 236, 153, 136, 116, 66, 139, 236, 153, 136,
-  ];
-
   #[test]
   fn parse_header_op_code_query() {
     let data = [0, 0, 0b00000000, 0, 0, 0, 0, 0, 0, 0, 0, 0];
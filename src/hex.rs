@@ -0,0 +1,351 @@
+use crate::shared::{ErrorCode, ParseError};
+
+const BYTES_PER_LINE: usize = 16;
+
+fn to_hex_dump_line(offset: usize, data: &[u8]) -> String {
+  let hex = data
+    .iter()
+    .map(|b| format!("{:02x}", b))
+    .collect::<Vec<String>>()
+    .join(" ");
+
+  let ascii = data
+    .iter()
+    .map(|b| {
+      if b.is_ascii_graphic() || *b == b' ' {
+        *b as char
+      } else {
+        '.'
+      }
+    })
+    .collect::<String>();
+
+  format!(
+    "{:04x}  {:width$}   {}",
+    offset,
+    hex,
+    ascii,
+    width = BYTES_PER_LINE * 3 - 1
+  )
+}
+
+pub fn to_hex_dump(data: &[u8]) -> String {
+  data
+    .chunks(BYTES_PER_LINE)
+    .enumerate()
+    .map(|(index, line)| to_hex_dump_line(index * BYTES_PER_LINE, line))
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+fn parse_hex_token(token: &str) -> Option<Vec<u8>> {
+  if token.is_empty() || !token.len().is_multiple_of(2) {
+    return None;
+  }
+
+  (0..token.len())
+    .step_by(2)
+    .map(|i| {
+      token
+        .get(i..i + 2)
+        .and_then(|b| u8::from_str_radix(b, 16).ok())
+    })
+    .collect()
+}
+
+fn split_with_gaps(line: &str) -> Vec<(usize, &str)> {
+  let mut tokens = vec![];
+  let mut gap = 0;
+  let mut start = None;
+
+  for (index, c) in line.char_indices() {
+    match (c.is_whitespace(), start) {
+      (true, Some(s)) => {
+        tokens.push((gap, &line[s..index]));
+        start = None;
+        gap = 1;
+      }
+      (true, None) => gap += 1,
+      (false, None) => start = Some(index),
+      (false, Some(_)) => {}
+    }
+  }
+
+  if let Some(s) = start {
+    tokens.push((gap, &line[s..]));
+  }
+
+  tokens
+}
+
+fn is_offset(token: &str, gap_after: Option<usize>, byte_count: usize) -> bool {
+  let (value, explicit) = match token.strip_suffix(':') {
+    Some(value) => (value, true),
+    None => (token, false),
+  };
+
+  let looks_like_offset =
+    explicit || value.len() >= 6 || (value.len() == 4 && gap_after.unwrap_or(0) >= 2);
+  let matches_byte_count = usize::from_str_radix(value, 16).ok() == Some(byte_count)
+    || usize::from_str_radix(value, 8).ok() == Some(byte_count);
+
+  looks_like_offset && matches_byte_count
+}
+
+fn parse_hex_dump_line(
+  line_number: usize,
+  line: &str,
+  data: &mut Vec<u8>,
+) -> Result<(), ParseError> {
+  let tokens = split_with_gaps(line);
+  let has_offset =
+    tokens.len() > 1 && is_offset(tokens[0].1, tokens.get(1).map(|t| t.0), data.len());
+  let tokens = if has_offset {
+    &tokens[1..]
+  } else {
+    &tokens[..]
+  };
+
+  let mut line_data = vec![];
+  for (index, (gap, token)) in tokens.iter().enumerate() {
+    let ends_hex_section = index > 0
+      && has_offset
+      && (line_data.len() >= BYTES_PER_LINE
+        || *gap >= 3
+        || (*gap == 2 && line_data.len() != BYTES_PER_LINE / 2));
+    if ends_hex_section {
+      break;
+    }
+
+    match parse_hex_token(token) {
+      Some(bytes) => line_data.extend(bytes),
+      None if has_offset && index > 0 => break,
+      None => {
+        return Err(ParseError::HexError(
+          ErrorCode::InvalidHex,
+          format!("Invalid hex on line {}: {}", line_number + 1, token),
+        ))
+      }
+    }
+  }
+
+  data.extend(line_data);
+  Ok(())
+}
+
+pub fn from_hex_dump(dump: &str) -> Result<Vec<u8>, ParseError> {
+  let lines = dump
+    .lines()
+    .map(|l| l.trim())
+    .enumerate()
+    .filter(|(_, l)| !l.is_empty())
+    .collect::<Vec<(usize, &str)>>();
+
+  let mut data = vec![];
+  for (index, (line_number, line)) in lines.iter().enumerate() {
+    let is_last_line = index == lines.len() - 1;
+    if is_last_line && !data.is_empty() && is_offset(line, Some(2), data.len()) {
+      break;
+    }
+    parse_hex_dump_line(*line_number, line, &mut data)?;
+  }
+
+  Ok(data)
+}
+
+fn base64_value(c: u8) -> Option<u8> {
+  match c {
+    b'A'..=b'Z' => Some(c - b'A'),
+    b'a'..=b'z' => Some(c - b'a' + 26),
+    b'0'..=b'9' => Some(c - b'0' + 52),
+    b'+' | b'-' => Some(62),
+    b'/' | b'_' => Some(63),
+    _ => None,
+  }
+}
+
+pub fn from_base64(input: &str) -> Result<Vec<u8>, ParseError> {
+  let input = input
+    .bytes()
+    .filter(|c| !c.is_ascii_whitespace())
+    .collect::<Vec<u8>>();
+  let unpadded = match input.iter().position(|c| *c == b'=') {
+    Some(index) if input[index..].iter().all(|c| *c == b'=') => &input[..index],
+    Some(_) => {
+      return Err(ParseError::HexError(
+        ErrorCode::InvalidHex,
+        "Base64 padding before end of input".to_owned(),
+      ))
+    }
+    None => &input[..],
+  };
+
+  if unpadded.len() % 4 == 1 {
+    return Err(ParseError::HexError(
+      ErrorCode::InvalidHex,
+      "Base64 input has an invalid length".to_owned(),
+    ));
+  }
+
+  let values = unpadded
+    .iter()
+    .map(|c| {
+      base64_value(*c).ok_or_else(|| {
+        ParseError::HexError(
+          ErrorCode::InvalidHex,
+          format!("Invalid base64 character: {}", *c as char),
+        )
+      })
+    })
+    .collect::<Result<Vec<u8>, ParseError>>()?;
+
+  Ok(
+    values
+      .chunks(4)
+      .flat_map(|chunk| {
+        let bits = chunk
+          .iter()
+          .enumerate()
+          .fold(0u32, |bits, (i, v)| bits | (*v as u32) << (18 - 6 * i));
+        let bytes = bits.to_be_bytes();
+        bytes[1..chunk.len()].to_vec()
+      })
+      .collect(),
+  )
+}
+
+mod test {
+
+  #[test]
+  fn to_hex_dump() {
+    let data = [
+      0, 2, 132, 0, 0, 0, 0, 1, 0, 0, 0, 3, 11, 95, 103, 111, 111, 103, 108, 101,
+    ];
+    let result = super::to_hex_dump(&data);
+    assert_eq!(
+      concat!(
+        "0000  00 02 84 00 00 00 00 01 00 00 00 03 0b 5f 67 6f   ............._go\n",
+        "0010  6f 67 6c 65                                       ogle"
+      ),
+      result
+    );
+  }
+
+  #[test]
+  fn to_hex_dump_empty() {
+    assert_eq!("", super::to_hex_dump(&[]));
+  }
+
+  #[test]
+  fn from_hex_dump_wireshark() {
+    let dump = "
+      0000   00 02 84 00 00 00 00 01 00 00 00 03 0b 5f 67 6f   ............._go
+      0010   6f 67 6c 65                                       ogle
+    ";
+    let result = super::from_hex_dump(dump);
+    assert_eq!(
+      Ok(vec![
+        0, 2, 132, 0, 0, 0, 0, 1, 0, 0, 0, 3, 11, 95, 103, 111, 111, 103, 108, 101
+      ]),
+      result
+    );
+  }
+
+  #[test]
+  fn from_hex_dump_wireshark_with_hex_like_ascii() {
+    let dump = "0000  00 02 84 00 00 00 00 01  00 00 00 03 0b 5f 67 6f   ............._go\n\
+                0010  62 65 65 66                                        beef";
+    let result = super::from_hex_dump(dump);
+    assert_eq!(
+      Ok(vec![
+        0, 2, 132, 0, 0, 0, 0, 1, 0, 0, 0, 3, 11, 95, 103, 111, 98, 101, 101, 102
+      ]),
+      result
+    );
+  }
+
+  #[test]
+  fn from_hex_dump_xxd() {
+    let dump = "00000000: 0002 8400 0000 0001 0000 0003 0b5f 676f  ............._go\n\
+                00000010: 6f67 6c65                                ogle";
+    let result = super::from_hex_dump(dump);
+    assert_eq!(
+      Ok(vec![
+        0, 2, 132, 0, 0, 0, 0, 1, 0, 0, 0, 3, 11, 95, 103, 111, 111, 103, 108, 101
+      ]),
+      result
+    );
+  }
+
+  #[test]
+  fn from_hex_dump_od() {
+    let dump = "0000000 00 02 84 00 00 00 00 01 00 00 00 03 0b 5f 67 6f\n\
+                0000020 6f 67 6c 65\n\
+                0000024";
+    let result = super::from_hex_dump(dump);
+    assert_eq!(
+      Ok(vec![
+        0, 2, 132, 0, 0, 0, 0, 1, 0, 0, 0, 3, 11, 95, 103, 111, 111, 103, 108, 101
+      ]),
+      result
+    );
+  }
+
+  #[test]
+  fn from_hex_dump_plain() {
+    for dump in &["00 02 84 00\n01", "0002840001", "0002 8400 01"] {
+      let result = super::from_hex_dump(dump);
+      assert_eq!(Ok(vec![0, 2, 132, 0, 1]), result);
+    }
+  }
+
+  #[test]
+  fn from_hex_dump_round_trip() {
+    let data = (0..=255).collect::<Vec<u8>>();
+    let result = super::from_hex_dump(&super::to_hex_dump(&data));
+    assert_eq!(Ok(data), result);
+  }
+
+  #[test]
+  fn from_hex_dump_and_fail() {
+    for dump in &["00 02 8", "00 0g", "hello"] {
+      match super::from_hex_dump(dump) {
+        Err(super::ParseError::HexError(..)) => {}
+        _ => panic!(),
+      }
+    }
+  }
+
+  #[test]
+  fn from_base64() {
+    let test_data = [
+      ("AAABAAABAAAAAAAAB2V4YW1wbGUDY29tAAABAAE", 29),
+      ("AAABAAABAAAAAAAAB2V4YW1wbGUDY29tAAABAAE=", 29),
+      ("AAAB AAAB\nAAAAAAAAB2V4YW1wbGUDY29tAAABAAE", 29),
+    ];
+
+    for td in &test_data {
+      let result = super::from_base64(td.0).unwrap();
+      assert_eq!(td.1, result.len());
+      assert_eq!(vec![0, 0, 1, 0, 0, 1], result[..6].to_vec());
+      assert_eq!(b"example", &result[13..20]);
+      assert_eq!(vec![0, 1, 0, 1], result[25..].to_vec());
+    }
+  }
+
+  #[test]
+  fn from_base64_url_safe() {
+    let result = super::from_base64("-_8");
+    assert_eq!(Ok(vec![251, 255]), result);
+  }
+
+  #[test]
+  fn from_base64_and_fail() {
+    for input in &["A", "AB=C", "AB*C"] {
+      match super::from_base64(input) {
+        Err(super::ParseError::HexError(..)) => {}
+        _ => panic!(),
+      }
+    }
+  }
+}
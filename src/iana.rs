@@ -0,0 +1,166 @@
+/*
+https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-4
+https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-2
+*/
+
+pub const RESOURCE_RECORD_TYPES: &[(u16, &str)] = &[
+  (1, "A"),
+  (2, "NS"),
+  (3, "MD"),
+  (4, "MF"),
+  (5, "CNAME"),
+  (6, "SOA"),
+  (7, "MB"),
+  (8, "MG"),
+  (9, "MR"),
+  (10, "NULL"),
+  (11, "WKS"),
+  (12, "PTR"),
+  (13, "HINFO"),
+  (14, "MINFO"),
+  (15, "MX"),
+  (16, "TXT"),
+  (17, "RP"),
+  (18, "AFSDB"),
+  (19, "X25"),
+  (20, "ISDN"),
+  (21, "RT"),
+  (22, "NSAP"),
+  (23, "NSAP-PTR"),
+  (24, "SIG"),
+  (25, "KEY"),
+  (26, "PX"),
+  (27, "GPOS"),
+  (28, "AAAA"),
+  (29, "LOC"),
+  (30, "NXT"),
+  (31, "EID"),
+  (32, "NIMLOC"),
+  (33, "SRV"),
+  (34, "ATMA"),
+  (35, "NAPTR"),
+  (36, "KX"),
+  (37, "CERT"),
+  (38, "A6"),
+  (39, "DNAME"),
+  (40, "SINK"),
+  (41, "OPT"),
+  (42, "APL"),
+  (43, "DS"),
+  (44, "SSHFP"),
+  (45, "IPSECKEY"),
+  (46, "RRSIG"),
+  (47, "NSEC"),
+  (48, "DNSKEY"),
+  (49, "DHCID"),
+  (50, "NSEC3"),
+  (51, "NSEC3PARAM"),
+  (52, "TLSA"),
+  (53, "SMIMEA"),
+  (55, "HIP"),
+  (56, "NINFO"),
+  (57, "RKEY"),
+  (58, "TALINK"),
+  (59, "CDS"),
+  (60, "CDNSKEY"),
+  (61, "OPENPGPKEY"),
+  (62, "CSYNC"),
+  (63, "ZONEMD"),
+  (64, "SVCB"),
+  (65, "HTTPS"),
+  (66, "DSYNC"),
+  (99, "SPF"),
+  (100, "UINFO"),
+  (101, "UID"),
+  (102, "GID"),
+  (103, "UNSPEC"),
+  (104, "NID"),
+  (105, "L32"),
+  (106, "L64"),
+  (107, "LP"),
+  (108, "EUI48"),
+  (109, "EUI64"),
+  (128, "NXNAME"),
+  (249, "TKEY"),
+  (250, "TSIG"),
+  (251, "IXFR"),
+  (252, "AXFR"),
+  (253, "MAILB"),
+  (254, "MAILA"),
+  (255, "ANY"),
+  (256, "URI"),
+  (257, "CAA"),
+  (258, "AVC"),
+  (259, "DOA"),
+  (260, "AMTRELAY"),
+  (261, "RESINFO"),
+  (262, "WALLET"),
+  (263, "CLA"),
+  (264, "IPN"),
+  (32768, "TA"),
+  (32769, "DLV"),
+];
+
+pub const CLASSES: &[(u16, &str)] = &[
+  (1, "IN"),
+  (2, "CS"),
+  (3, "CH"),
+  (4, "HS"),
+  (254, "NONE"),
+  (255, "ANY"),
+];
+
+fn mnemonic(table: &[(u16, &'static str)], code: u16) -> Option<&'static str> {
+  table.iter().find(|(c, _)| *c == code).map(|(_, m)| *m)
+}
+
+fn code(table: &[(u16, &str)], prefix: &str, name: &str) -> Option<u16> {
+  table
+    .iter()
+    .find(|(_, m)| m.eq_ignore_ascii_case(name))
+    .map(|(c, _)| *c)
+    .or_else(|| {
+      let upper = name.to_ascii_uppercase();
+      upper.strip_prefix(prefix)?.parse::<u16>().ok()
+    })
+}
+
+pub fn type_mnemonic(code: u16) -> Option<&'static str> {
+  mnemonic(RESOURCE_RECORD_TYPES, code)
+}
+
+pub fn type_code(name: &str) -> Option<u16> {
+  code(RESOURCE_RECORD_TYPES, "TYPE", name)
+}
+
+pub fn class_mnemonic(code: u16) -> Option<&'static str> {
+  mnemonic(CLASSES, code)
+}
+
+pub fn class_code(name: &str) -> Option<u16> {
+  code(CLASSES, "CLASS", name)
+}
+
+mod test {
+
+  #[test]
+  fn type_code() {
+    assert_eq!(Some(28), super::type_code("aaaa"));
+    assert_eq!(Some(65280), super::type_code("TYPE65280"));
+    assert_eq!(None, super::type_code("TYPE70000"));
+    assert_eq!(None, super::type_code("BOGUS"));
+  }
+
+  #[test]
+  fn type_mnemonic() {
+    assert_eq!(Some("CAA"), super::type_mnemonic(257));
+    assert_eq!(None, super::type_mnemonic(65280));
+  }
+
+  #[test]
+  fn class_code() {
+    assert_eq!(Some(1), super::class_code("in"));
+    assert_eq!(Some(255), super::class_code("ANY"));
+    assert_eq!(Some(32), super::class_code("CLASS32"));
+  }
+}
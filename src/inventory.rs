@@ -0,0 +1,311 @@
+use crate::edns::{format_hardware_address, owner_option, HardwareAddress};
+use crate::message::Message;
+use crate::name::Name;
+use crate::resource_record::{ResourceRecord, ResourceRecordData};
+use crate::service_instance::parse_service_instance_name;
+use crate::txt::{parse_txt_attributes, txt_hardware_address, TxtAttribute};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InventoryEntry {
+  pub instance: String,
+  pub service_type: String,
+  pub host: Option<String>,
+  pub port: Option<u16>,
+  pub addresses: Vec<IpAddr>,
+  pub hardware_address: Option<HardwareAddress>,
+  pub txt: Vec<TxtAttribute>,
+}
+
+#[derive(Default)]
+struct InventoryService {
+  host: Option<String>,
+  port: Option<u16>,
+  hardware_address: Option<HardwareAddress>,
+  txt: Vec<TxtAttribute>,
+}
+
+#[derive(Default)]
+pub struct Inventory {
+  services: BTreeMap<Name, InventoryService>,
+  addresses: BTreeMap<Name, Vec<IpAddr>>,
+}
+
+impl Inventory {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn service(&mut self, name: &str) -> &mut InventoryService {
+    self.services.entry(Name::new(name)).or_default()
+  }
+
+  fn add_address(&mut self, host: &str, address: IpAddr) {
+    let addresses = self.addresses.entry(Name::new(host)).or_default();
+    if !addresses.contains(&address) {
+      addresses.push(address);
+    }
+  }
+
+  fn add_resource_record(&mut self, record: &ResourceRecord, owner: Option<HardwareAddress>) {
+    match &record.resource_record_data {
+      ResourceRecordData::PTR(instance) if parse_service_instance_name(instance).is_ok() => {
+        self.service(instance);
+      }
+      ResourceRecordData::SRV(srv) if parse_service_instance_name(&record.name).is_ok() => {
+        let service = self.service(&record.name);
+        service.host = Some(srv.target.clone());
+        service.port = Some(srv.port);
+        if owner.is_some() {
+          service.hardware_address = owner;
+        }
+      }
+      ResourceRecordData::TXT(strings) if parse_service_instance_name(&record.name).is_ok() => {
+        self.service(&record.name).txt = parse_txt_attributes(strings);
+      }
+      ResourceRecordData::A(address) => self.add_address(&record.name, IpAddr::V4(*address)),
+      ResourceRecordData::AAAA(address) => self.add_address(&record.name, IpAddr::V6(*address)),
+      _ => {}
+    }
+  }
+
+  pub fn add(&mut self, message: &Message) {
+    let owner = owner_option(message).map(|o| o.primary_mac);
+    message
+      .answers
+      .iter()
+      .chain(message.name_servers.iter())
+      .chain(message.additional_records.iter())
+      .filter(|r| r.ttl > 0)
+      .for_each(|r| self.add_resource_record(r, owner));
+  }
+
+  pub fn entries(&self) -> Vec<InventoryEntry> {
+    self
+      .services
+      .iter()
+      .filter_map(|(name, service)| {
+        let name = parse_service_instance_name(name.as_str()).ok()?;
+        let addresses = service
+          .host
+          .as_ref()
+          .and_then(|host| self.addresses.get(&Name::new(host)))
+          .cloned()
+          .unwrap_or_default();
+        Some(InventoryEntry {
+          instance: name.instance,
+          service_type: name.service_type,
+          host: service.host.clone(),
+          port: service.port,
+          addresses,
+          hardware_address: service
+            .hardware_address
+            .or_else(|| txt_hardware_address(&service.txt)),
+          txt: service.txt.clone(),
+        })
+      })
+      .collect()
+  }
+
+  pub fn to_csv(&self) -> String {
+    let mut csv = "instance,service_type,host,port,addresses,hardware_address,txt\n".to_owned();
+    for entry in self.entries() {
+      let fields = [
+        entry.instance.clone(),
+        entry.service_type.clone(),
+        entry.host.clone().unwrap_or_default(),
+        entry.port.map(|p| p.to_string()).unwrap_or_default(),
+        join_addresses(&entry.addresses, " "),
+        entry
+          .hardware_address
+          .map(|a| format_hardware_address(&a))
+          .unwrap_or_default(),
+        entry
+          .txt
+          .iter()
+          .map(|(key, value)| match value {
+            Some(value) => format!("{}={}", key, value),
+            None => key.clone(),
+          })
+          .collect::<Vec<String>>()
+          .join(" "),
+      ];
+      csv.push_str(
+        &fields
+          .iter()
+          .map(|f| csv_field(f))
+          .collect::<Vec<String>>()
+          .join(","),
+      );
+      csv.push('\n');
+    }
+    csv
+  }
+
+  pub fn to_json(&self) -> String {
+    let entries = self
+      .entries()
+      .iter()
+      .map(|entry| {
+        let txt = entry
+          .txt
+          .iter()
+          .map(|(key, value)| {
+            let value = match value {
+              Some(value) => json_string(&value.to_string()),
+              None => "true".to_owned(),
+            };
+            format!("{}:{}", json_string(key), value)
+          })
+          .collect::<Vec<String>>()
+          .join(",");
+        let addresses = entry
+          .addresses
+          .iter()
+          .map(|a| json_string(&a.to_string()))
+          .collect::<Vec<String>>()
+          .join(",");
+        format!(
+          "{{\"instance\":{},\"service_type\":{},\"host\":{},\"port\":{},\"addresses\":[{}],\"hardware_address\":{},\"txt\":{{{}}}}}",
+          json_string(&entry.instance),
+          json_string(&entry.service_type),
+          entry
+            .host
+            .as_ref()
+            .map(|h| json_string(h))
+            .unwrap_or_else(|| "null".to_owned()),
+          entry
+            .port
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| "null".to_owned()),
+          addresses,
+          entry
+            .hardware_address
+            .map(|a| json_string(&format_hardware_address(&a)))
+            .unwrap_or_else(|| "null".to_owned()),
+          txt
+        )
+      })
+      .collect::<Vec<String>>();
+    format!("[{}]", entries.join(","))
+  }
+}
+
+fn join_addresses(addresses: &[IpAddr], separator: &str) -> String {
+  addresses
+    .iter()
+    .map(|a| a.to_string())
+    .collect::<Vec<String>>()
+    .join(separator)
+}
+
+fn csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_owned()
+  }
+}
+
+fn json_string(value: &str) -> String {
+  let mut json = "\"".to_owned();
+  for c in value.chars() {
+    match c {
+      '"' => json.push_str("\\\""),
+      '\\' => json.push_str("\\\\"),
+      '\n' => json.push_str("\\n"),
+      '\r' => json.push_str("\\r"),
+      '\t' => json.push_str("\\t"),
+      c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+      c => json.push(c),
+    }
+  }
+  json.push('"');
+  json
+}
+
+#[cfg(test)]
+mod test {
+
+  fn inventory() -> super::Inventory {
+    let mut inventory = super::Inventory::new();
+    inventory.add(&crate::test_support::response(&[
+      "_googlecast._tcp.local. 120 IN PTR Living\\ Room\\,\\ 1._googlecast._tcp.local.",
+      "Living\\ Room\\,\\ 1._googlecast._tcp.local. 120 IN SRV 0 0 8009 e0719ee5.local.",
+    ]));
+    inventory.add(&crate::test_support::response(&[
+      "Living\\ Room\\,\\ 1._googlecast._tcp.local. 4500 IN TXT \"md=Google Home Mini\" \"nf\"",
+      "e0719ee5.local. 120 IN A 192.168.1.137",
+      "E0719EE5.local. 120 IN AAAA fe80::1",
+      "other.local. 120 IN A 192.168.1.2",
+    ]));
+    inventory
+  }
+
+  #[test]
+  fn entries() {
+    let entries = inventory().entries();
+    assert_eq!(
+      vec![super::InventoryEntry {
+        instance: "Living Room, 1".to_owned(),
+        service_type: "_googlecast._tcp".to_owned(),
+        host: Some("e0719ee5.local".to_owned()),
+        port: Some(8009),
+        addresses: vec!["192.168.1.137".parse().unwrap(), "fe80::1".parse().unwrap()],
+        hardware_address: None,
+        txt: vec![
+          (
+            "md".to_owned(),
+            Some(crate::txt::TxtValue::Text("Google Home Mini".to_owned()))
+          ),
+          ("nf".to_owned(), None)
+        ],
+      }],
+      entries
+    );
+  }
+
+  #[test]
+  fn entries_with_mixed_case_names() {
+    let mut inventory = super::Inventory::new();
+    inventory.add(&crate::test_support::response(&[
+      "_ipp._tcp.local. 4500 IN PTR Printer._ipp._tcp.local.",
+      "PRINTER._IPP._TCP.LOCAL. 120 IN SRV 0 0 631 Host.local.",
+      "printer._ipp._tcp.local. 4500 IN TXT \"rp=ipp/print\"",
+      "HOST.LOCAL. 120 IN A 192.168.1.10",
+    ]));
+    let entries = inventory.entries();
+    assert_eq!(1, entries.len());
+    assert_eq!("Printer", entries[0].instance);
+    assert_eq!(Some(631), entries[0].port);
+    assert_eq!(
+      vec!["192.168.1.10".parse::<std::net::IpAddr>().unwrap()],
+      entries[0].addresses
+    );
+    assert_eq!(1, entries[0].txt.len());
+  }
+
+  #[test]
+  fn to_csv() {
+    assert_eq!(
+      concat!(
+        "instance,service_type,host,port,addresses,hardware_address,txt\n",
+        "\"Living Room, 1\",_googlecast._tcp,e0719ee5.local,8009,192.168.1.137 fe80::1,,md=Google Home Mini nf\n"
+      ),
+      inventory().to_csv()
+    );
+  }
+
+  #[test]
+  fn to_json() {
+    assert_eq!(
+      concat!(
+        "[{\"instance\":\"Living Room, 1\",\"service_type\":\"_googlecast._tcp\",",
+        "\"host\":\"e0719ee5.local\",\"port\":8009,\"addresses\":[\"192.168.1.137\",\"fe80::1\"],\"hardware_address\":null,",
+        "\"txt\":{\"md\":\"Google Home Mini\",\"nf\":true}}]"
+      ),
+      inventory().to_json()
+    );
+  }
+}
@@ -1,5 +1,31 @@
+pub mod address_selection;
+pub mod annotation;
+pub mod classification;
+pub mod compression;
+pub mod conflict;
+pub mod context;
+pub mod diff;
+pub mod edns;
+pub mod fingerprint;
+pub mod hash;
 pub mod header;
+pub mod hex;
+pub mod iana;
+pub mod inventory;
+pub mod lint;
 pub mod message;
+pub mod name;
+pub mod normalize;
 pub mod query;
+pub mod referral;
+pub mod registry;
+pub mod resolv_conf;
 pub mod resource_record;
+pub mod service_instance;
 pub mod shared;
+pub mod sleep_proxy;
+pub mod stamp;
+pub mod txt;
+
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,323 @@
+use crate::header::QueryOrResponse;
+use crate::message::Message;
+use crate::name::Name;
+use crate::resource_record::{RecordType, ResourceRecord, ResourceRecordData};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
+const HOST_RECORD_TTL: u32 = 120;
+const OTHER_RECORD_TTL: u32 = 4500;
+const RECOMMENDED_TXT_SIZE: u16 = 200;
+const SERVICE_TYPE_ENUMERATION: &str = "_services._dns-sd._udp.local";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LintKind {
+  UnexpectedTtl { ttl: u32, expected: u32 },
+  MissingCacheFlush,
+  SrvTargetOutsideLocal(String),
+  TxtLargerThanRecommended(u16),
+  MissingAdditionalRecord,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct LintWarning {
+  pub name: String,
+  pub resource_record_type: RecordType,
+  pub kind: LintKind,
+}
+
+fn is_host_record(resource_record_type: &RecordType) -> bool {
+  matches!(
+    resource_record_type,
+    RecordType::A | RecordType::AAAA | RecordType::SRV
+  )
+}
+
+fn is_unique_record(resource_record_type: &RecordType) -> bool {
+  is_host_record(resource_record_type) || *resource_record_type == RecordType::TXT
+}
+
+fn is_local_name(name: &str) -> bool {
+  let name = name.trim_end_matches('.').to_ascii_lowercase();
+  name == "local" || name.ends_with(".local")
+}
+
+fn lint_resource_record(record: &ResourceRecord) -> Vec<LintKind> {
+  let mut kinds = vec![];
+
+  if record.resource_record_type == RecordType::OPT {
+    return kinds;
+  }
+
+  let expected_ttl = if is_host_record(&record.resource_record_type) {
+    HOST_RECORD_TTL
+  } else {
+    OTHER_RECORD_TTL
+  };
+  if record.ttl != 0 && record.ttl != expected_ttl {
+    kinds.push(LintKind::UnexpectedTtl {
+      ttl: record.ttl,
+      expected: expected_ttl,
+    });
+  }
+
+  if is_unique_record(&record.resource_record_type) && !record.cache_flush {
+    kinds.push(LintKind::MissingCacheFlush);
+  }
+
+  match &record.resource_record_data {
+    ResourceRecordData::SRV(srv) if !is_local_name(&srv.target) => {
+      kinds.push(LintKind::SrvTargetOutsideLocal(srv.target.clone()));
+    }
+    ResourceRecordData::TXT(_) if record.resource_record_data_length > RECOMMENDED_TXT_SIZE => {
+      kinds.push(LintKind::TxtLargerThanRecommended(
+        record.resource_record_data_length,
+      ));
+    }
+    _ => {}
+  }
+
+  kinds
+}
+
+pub fn lint_message(message: &Message) -> Vec<LintWarning> {
+  if message.header.query_or_response != QueryOrResponse::Response {
+    return vec![];
+  }
+
+  message
+    .answers
+    .iter()
+    .chain(message.name_servers.iter())
+    .chain(message.additional_records.iter())
+    .flat_map(|record| {
+      lint_resource_record(record)
+        .into_iter()
+        .map(move |kind| LintWarning {
+          name: record.name.clone(),
+          resource_record_type: record.resource_record_type.clone(),
+          kind,
+        })
+    })
+    .collect()
+}
+
+fn has_record(message: &Message, name: &str, resource_record_types: &[RecordType]) -> bool {
+  let name = Name::new(name);
+  message
+    .answers
+    .iter()
+    .chain(message.name_servers.iter())
+    .chain(message.additional_records.iter())
+    .any(|r| resource_record_types.contains(&r.resource_record_type) && Name::new(&r.name) == name)
+}
+
+fn missing(name: &str, resource_record_type: RecordType) -> LintWarning {
+  LintWarning {
+    name: name.to_owned(),
+    resource_record_type,
+    kind: LintKind::MissingAdditionalRecord,
+  }
+}
+
+fn missing_addresses(message: &Message, target: &str) -> Option<LintWarning> {
+  match has_record(message, target, &[RecordType::A, RecordType::AAAA]) {
+    true => None,
+    false => Some(missing(target, RecordType::A)),
+  }
+}
+
+pub fn lint_dns_sd_completeness(message: &Message) -> Vec<LintWarning> {
+  if message.header.query_or_response != QueryOrResponse::Response {
+    return vec![];
+  }
+
+  let mut warnings = vec![];
+  let srv_records = message
+    .answers
+    .iter()
+    .chain(message.name_servers.iter())
+    .chain(message.additional_records.iter())
+    .filter_map(|r| match &r.resource_record_data {
+      ResourceRecordData::SRV(srv) => Some((r.name.as_str(), srv.target.as_str())),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+
+  for record in &message.answers {
+    match &record.resource_record_data {
+      ResourceRecordData::PTR(instance)
+        if Name::new(&record.name) != Name::new(SERVICE_TYPE_ENUMERATION) =>
+      {
+        let instance_name = Name::new(instance);
+        let targets = srv_records
+          .iter()
+          .filter(|(name, _)| Name::new(name) == instance_name)
+          .map(|(_, target)| *target)
+          .collect::<Vec<_>>();
+        if targets.is_empty() {
+          warnings.push(missing(instance, RecordType::SRV));
+        }
+        if !has_record(message, instance, &[RecordType::TXT]) {
+          warnings.push(missing(instance, RecordType::TXT));
+        }
+        warnings.extend(
+          targets
+            .into_iter()
+            .filter_map(|target| missing_addresses(message, target)),
+        );
+      }
+      ResourceRecordData::SRV(srv) => warnings.extend(missing_addresses(message, &srv.target)),
+      _ => {}
+    }
+  }
+
+  warnings.into_iter().fold(vec![], |mut unique, warning| {
+    if !unique.contains(&warning) {
+      unique.push(warning);
+    }
+    unique
+  })
+}
+
+pub fn lint_messages<'a, I>(messages: I) -> BTreeMap<SocketAddr, Vec<LintWarning>>
+where
+  I: IntoIterator<Item = (SocketAddr, &'a Message)>,
+{
+  messages
+    .into_iter()
+    .fold(BTreeMap::new(), |mut warnings, (source, message)| {
+      let message_warnings = lint_message(message);
+      if !message_warnings.is_empty() {
+        warnings
+          .entry(source)
+          .or_insert_with(Vec::new)
+          .extend(message_warnings);
+      }
+      warnings
+    })
+}
+
+#[cfg(test)]
+mod test {
+
+  fn response(lines: &[&str]) -> crate::message::Message {
+    let mut message = crate::test_support::response(lines);
+    message
+      .answers
+      .iter_mut()
+      .for_each(|r| r.cache_flush = r.resource_record_type != super::RecordType::PTR);
+    message
+  }
+
+  #[test]
+  fn lint_message_without_warnings() {
+    let message = response(&[
+      "_hap._tcp.local. 4500 IN PTR Bridge._hap._tcp.local.",
+      "Bridge._hap._tcp.local. 120 IN SRV 0 0 51827 bridge.local.",
+      "Bridge._hap._tcp.local. 4500 IN TXT \"c#=2\" \"ff=1\"",
+      "bridge.local. 120 IN A 192.168.1.10",
+      "bridge.local. 0 IN A 192.168.1.11",
+    ]);
+    assert_eq!(
+      Vec::<super::LintWarning>::new(),
+      super::lint_message(&message)
+    );
+  }
+
+  #[test]
+  fn lint_message_with_parsed_cache_flush() {
+    let data = include_bytes!("../tests/corpus/googlecast_response.bin");
+    let message =
+      crate::message::parse_with_options(data, &crate::shared::Profile::PermissiveMdns.into())
+        .unwrap();
+    assert!(super::lint_message(&message)
+      .iter()
+      .all(|w| w.kind != super::LintKind::MissingCacheFlush));
+  }
+
+  #[test]
+  fn lint_message() {
+    let mut message = response(&[
+      "bridge.local. 60 IN A 192.168.1.10",
+      "Bridge._hap._tcp.local. 120 IN SRV 0 0 51827 bridge.example.com.",
+    ]);
+    message.answers[0].cache_flush = false;
+
+    let kinds = super::lint_message(&message)
+      .into_iter()
+      .map(|w| w.kind)
+      .collect::<Vec<super::LintKind>>();
+    assert_eq!(
+      vec![
+        super::LintKind::UnexpectedTtl {
+          ttl: 60,
+          expected: 120
+        },
+        super::LintKind::MissingCacheFlush,
+        super::LintKind::SrvTargetOutsideLocal("bridge.example.com".to_owned()),
+      ],
+      kinds
+    );
+  }
+
+  #[test]
+  fn lint_message_with_large_txt() {
+    let line = format!(
+      "Bridge._hap._tcp.local. 4500 IN TXT \"{}\" \"{}\"",
+      "a".repeat(150),
+      "b".repeat(150)
+    );
+    let message = response(&[&line]);
+    let warnings = super::lint_message(&message);
+    assert_eq!(
+      vec![super::LintKind::TxtLargerThanRecommended(302)],
+      warnings.into_iter().map(|w| w.kind).collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn lint_dns_sd_completeness() {
+    let complete = response(&[
+      "_hap._tcp.local. 4500 IN PTR Bridge._hap._tcp.local.",
+      "Bridge._hap._tcp.local. 120 IN SRV 0 0 51827 bridge.local.",
+      "Bridge._hap._tcp.local. 4500 IN TXT \"c#=2\"",
+      "bridge.local. 120 IN AAAA fd00::10",
+    ]);
+    assert_eq!(
+      Vec::<super::LintWarning>::new(),
+      super::lint_dns_sd_completeness(&complete)
+    );
+
+    let incomplete = response(&[
+      "_services._dns-sd._udp.local. 4500 IN PTR _hap._tcp.local.",
+      "_hap._tcp.local. 4500 IN PTR Bridge._hap._tcp.local.",
+      "Bridge._hap._tcp.local. 120 IN SRV 0 0 51827 bridge.local.",
+      "_ipp._tcp.local. 4500 IN PTR Printer._ipp._tcp.local.",
+    ]);
+    assert_eq!(
+      vec![
+        ("Bridge._hap._tcp.local", super::RecordType::TXT),
+        ("bridge.local", super::RecordType::A),
+        ("Printer._ipp._tcp.local", super::RecordType::SRV),
+        ("Printer._ipp._tcp.local", super::RecordType::TXT),
+      ],
+      super::lint_dns_sd_completeness(&incomplete)
+        .iter()
+        .map(|w| (w.name.as_str(), w.resource_record_type.clone()))
+        .collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn lint_messages() {
+    let first: std::net::SocketAddr = "192.168.1.10:5353".parse().unwrap();
+    let second: std::net::SocketAddr = "192.168.1.20:5353".parse().unwrap();
+    let bad = response(&["bridge.local. 60 IN A 192.168.1.10"]);
+    let good = response(&["other.local. 120 IN A 192.168.1.20"]);
+
+    let warnings = super::lint_messages(vec![(first, &bad), (second, &good), (first, &bad)]);
+    assert_eq!(vec![&first], warnings.keys().collect::<Vec<_>>());
+    assert_eq!(2, warnings[&first].len());
+  }
+}
@@ -1,8 +1,9 @@
 use crate::header::{parse_header, Header};
-use crate::query::{parse_queries, Query};
+use crate::query::{parse_queries, parse_query, Query};
 use crate::resource_record::{parse_resource_records, ResourceRecord};
-use crate::shared::Label;
-use crate::shared::ParseError;
+use crate::shared::{parse_name, Label};
+use crate::shared::{ErrorCode, Limits, ParseError, ParseOptions, Section};
+use std::ops::Range;
 /*
 https://justanapplication.wordpress.com/category/dns/dns-resource-records/dns-srv-record/
 
@@ -17,13 +18,42 @@ https://flylib.com/books/en/3.223.1.151/1/
 https://tools.ietf.org/html/rfc1035 -> 4.1.1
 */
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Message {
   pub header: Header,
   pub queries: Vec<Query>,
   pub answers: Vec<ResourceRecord>,
   pub name_servers: Vec<ResourceRecord>,
   pub additional_records: Vec<ResourceRecord>,
+  pub raw: Option<Vec<u8>>,
+}
+
+impl Message {
+  pub fn peek(data: &[u8], options: &ParseOptions) -> Result<(Header, Option<Query>), ParseError> {
+    let header = parse_header(data)?;
+    let query = match header.question_count {
+      0 => None,
+      _ => Some(
+        parse_query(&mut vec![], 12, data, options)
+          .map_err(|e| ParseError::SectionError(Section::Question, 0, Box::new(e)))?,
+      ),
+    };
+    Ok((header, query))
+  }
+
+  pub fn encoded_len(&self) -> usize {
+    let queries = self.queries.iter().fold(12, |sum, q| sum + q.encoded_len());
+    self
+      .answers
+      .iter()
+      .chain(self.name_servers.iter())
+      .chain(self.additional_records.iter())
+      .fold(queries, |sum, r| sum + r.encoded_len())
+  }
+
+  pub fn bytes(&self, range: &Range<usize>) -> Option<&[u8]> {
+    self.raw.as_ref().and_then(|raw| raw.get(range.clone()))
+  }
 }
 
 fn parse_additional_resource_records(
@@ -31,8 +61,16 @@ fn parse_additional_resource_records(
   offset: usize,
   header: &Header,
   data: &[u8],
+  options: &ParseOptions,
 ) -> Result<Vec<ResourceRecord>, ParseError> {
-  parse_resource_records(label_store, offset, header.additional_count, data)
+  parse_resource_records(
+    label_store,
+    offset,
+    header.additional_count,
+    data,
+    options,
+    Section::Additional,
+  )
 }
 
 fn parse_name_servers(
@@ -40,8 +78,16 @@ fn parse_name_servers(
   offset: usize,
   header: &Header,
   data: &[u8],
+  options: &ParseOptions,
 ) -> Result<Vec<ResourceRecord>, ParseError> {
-  parse_resource_records(label_store, offset, header.name_server_count, data)
+  parse_resource_records(
+    label_store,
+    offset,
+    header.name_server_count,
+    data,
+    options,
+    Section::Authority,
+  )
 }
 
 fn parse_answers(
@@ -49,24 +95,71 @@ fn parse_answers(
   offset: usize,
   header: &Header,
   data: &[u8],
+  options: &ParseOptions,
 ) -> Result<Vec<ResourceRecord>, ParseError> {
-  parse_resource_records(label_store, offset, header.answer_count, data)
+  parse_resource_records(
+    label_store,
+    offset,
+    header.answer_count,
+    data,
+    options,
+    Section::Answer,
+  )
+}
+
+fn check_record_counts(header: &Header, limits: &Limits) -> Result<(), ParseError> {
+  let counts = [
+    header.question_count,
+    header.answer_count,
+    header.name_server_count,
+    header.additional_count,
+  ];
+  match counts.iter().find(|c| **c > limits.max_records) {
+    Some(count) => Err(ParseError::LimitError(
+      ErrorCode::LimitExceeded,
+      format!(
+        "Record count {} exceeds limit of {}",
+        count, limits.max_records
+      ),
+    )),
+    None => Ok(()),
+  }
+}
+
+fn check_message_size(data: &[u8], limits: &Limits) -> Result<(), ParseError> {
+  if data.len() > limits.max_message_size {
+    return Err(ParseError::LimitError(
+      ErrorCode::LimitExceeded,
+      format!(
+        "Message size {} exceeds limit of {}",
+        data.len(),
+        limits.max_message_size
+      ),
+    ));
+  }
+  Ok(())
 }
 
 pub fn parse(data: &[u8]) -> Result<Message, ParseError> {
+  parse_with_options(data, &ParseOptions::default())
+}
+
+fn parse_message(data: &[u8], options: &ParseOptions) -> Result<(Message, usize), ParseError> {
+  check_message_size(data, &options.limits)?;
   let header = parse_header(data)?;
+  check_record_counts(&header, &options.limits)?;
 
   let offset = 12;
 
   let mut label_store = vec![];
 
-  let queries = parse_queries(&mut label_store, offset, &header, data)?;
+  let queries = parse_queries(&mut label_store, offset, &header, data, options)?;
   let queries_length = queries.iter().fold(offset, |sum, q| sum + q.size());
 
-  let answers = parse_answers(&mut label_store, queries_length, &header, data)?;
+  let answers = parse_answers(&mut label_store, queries_length, &header, data, options)?;
   let answers_length = answers.iter().fold(queries_length, |sum, a| sum + a.size());
 
-  let name_servers = parse_name_servers(&mut label_store, answers_length, &header, data)?;
+  let name_servers = parse_name_servers(&mut label_store, answers_length, &header, data, options)?;
   let name_server_resources_length = name_servers
     .iter()
     .fold(answers_length, |sum, r| sum + r.size());
@@ -76,28 +169,509 @@ pub fn parse(data: &[u8]) -> Result<Message, ParseError> {
     name_server_resources_length,
     &header,
     data,
+    options,
   )?;
+  let message_length = additional_records
+    .iter()
+    .fold(name_server_resources_length, |sum, r| sum + r.size());
 
-  Ok(Message {
+  let message = Message {
     header,
     queries,
     answers,
     name_servers,
     additional_records,
+    raw: match options.retain_raw {
+      true => Some(data[..message_length].to_vec()),
+      false => None,
+    },
+  };
+  Ok((message, message_length))
+}
+
+pub fn parse_with_options(data: &[u8], options: &ParseOptions) -> Result<Message, ParseError> {
+  let (message, message_length) = parse_message(data, options)?;
+
+  if message_length < data.len() && !options.allow_trailing_data {
+    return Err(ParseError::MessageError(
+      ErrorCode::TrailingData,
+      format!(
+        "Message ends at {} but data is {} bytes long",
+        message_length,
+        data.len()
+      ),
+    ));
+  }
+
+  Ok(message)
+}
+
+pub struct MessageStream<'a> {
+  data: &'a [u8],
+  offset: usize,
+  options: ParseOptions,
+  failed: bool,
+}
+
+impl<'a> MessageStream<'a> {
+  pub fn offset(&self) -> usize {
+    self.offset
+  }
+
+  pub fn resume_at(&mut self, offset: usize) {
+    self.offset = offset.min(self.data.len());
+    self.failed = false;
+  }
+
+  pub fn skip_bytes(&mut self, length: usize) {
+    self.resume_at(self.offset + length);
+  }
+}
+
+impl<'a> Iterator for MessageStream<'a> {
+  type Item = Result<(Message, usize), ParseError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.failed || self.offset >= self.data.len() {
+      return None;
+    }
+
+    let end = self
+      .data
+      .len()
+      .min(self.offset + self.options.limits.max_message_size);
+    match parse_message(&self.data[self.offset..end], &self.options) {
+      Ok((message, length)) => {
+        self.offset += length;
+        Some(Ok((message, length)))
+      }
+      Err(e) => {
+        self.failed = true;
+        Some(Err(e))
+      }
+    }
+  }
+}
+
+pub fn parse_stream(data: &[u8]) -> MessageStream<'_> {
+  parse_stream_with_options(data, &ParseOptions::default())
+}
+
+pub fn parse_stream_with_options<'a>(data: &'a [u8], options: &ParseOptions) -> MessageStream<'a> {
+  MessageStream {
+    data,
+    offset: 0,
+    options: options.clone(),
+    failed: false,
+  }
+}
+
+fn skip_resource_records(
+  offset: usize,
+  count: u16,
+  data: &[u8],
+  limits: &Limits,
+) -> Result<usize, ParseError> {
+  let mut offset = offset;
+  for _ in 0..count {
+    let name_end = parse_name(offset, data, limits)?
+      .iter()
+      .fold(offset, |sum, l| sum + l.size());
+    if data.len() < name_end + 10 {
+      return Err(ParseError::ResourceRecordError(
+        ErrorCode::TruncatedRecord,
+        "Data not long enough for resource record".to_owned(),
+      ));
+    }
+    let length = u16::from_be_bytes([data[name_end + 8], data[name_end + 9]]) as usize;
+    offset = name_end + 10 + length;
+    if data.len() < offset {
+      return Err(ParseError::ResourceRecordError(
+        ErrorCode::TruncatedRecord,
+        "Data would overflow when skipping resource record".to_owned(),
+      ));
+    }
+  }
+  Ok(offset)
+}
+
+pub struct LazyMessage<'a> {
+  pub header: Header,
+  pub queries: Vec<Query>,
+  data: &'a [u8],
+  options: ParseOptions,
+  ranges: [Range<usize>; 3],
+  label_store: Vec<Label>,
+  sections: Vec<Vec<ResourceRecord>>,
+}
+
+impl<'a> LazyMessage<'a> {
+  fn index(section: Section) -> Option<usize> {
+    match section {
+      Section::Question => None,
+      Section::Answer => Some(0),
+      Section::Authority => Some(1),
+      Section::Additional => Some(2),
+    }
+  }
+
+  pub fn section_range(&self, section: Section) -> Range<usize> {
+    match Self::index(section) {
+      Some(index) => self.ranges[index].clone(),
+      None => 12..self.ranges[0].start,
+    }
+  }
+
+  pub fn section(&mut self, section: Section) -> Result<&[ResourceRecord], ParseError> {
+    let index = match Self::index(section) {
+      Some(index) => index,
+      None => {
+        return Err(ParseError::MessageError(
+          ErrorCode::InvalidSection,
+          "Questions are not resource records, use queries instead".to_owned(),
+        ))
+      }
+    };
+    let sections = [Section::Answer, Section::Authority, Section::Additional];
+    let counts = [
+      self.header.answer_count,
+      self.header.name_server_count,
+      self.header.additional_count,
+    ];
+
+    while self.sections.len() <= index {
+      let current = self.sections.len();
+      let label_count = self.label_store.len();
+      let records = parse_resource_records(
+        &mut self.label_store,
+        self.ranges[current].start,
+        counts[current],
+        self.data,
+        &self.options,
+        sections[current],
+      )
+      .inspect_err(|_| self.label_store.truncate(label_count))?;
+      self.sections.push(records);
+    }
+
+    Ok(&self.sections[index])
+  }
+
+  pub fn answers(&mut self) -> Result<&[ResourceRecord], ParseError> {
+    self.section(Section::Answer)
+  }
+
+  pub fn name_servers(&mut self) -> Result<&[ResourceRecord], ParseError> {
+    self.section(Section::Authority)
+  }
+
+  pub fn additional_records(&mut self) -> Result<&[ResourceRecord], ParseError> {
+    self.section(Section::Additional)
+  }
+}
+
+pub fn parse_lazy(data: &[u8]) -> Result<LazyMessage<'_>, ParseError> {
+  parse_lazy_with_options(data, &ParseOptions::default())
+}
+
+pub fn parse_lazy_with_options<'a>(
+  data: &'a [u8],
+  options: &ParseOptions,
+) -> Result<LazyMessage<'a>, ParseError> {
+  check_message_size(data, &options.limits)?;
+  let header = parse_header(data)?;
+  check_record_counts(&header, &options.limits)?;
+
+  let mut label_store = vec![];
+  let queries = parse_queries(&mut label_store, 12, &header, data, options)?;
+
+  let limits = &options.limits;
+  let answers_start = queries.iter().fold(12, |sum, q| sum + q.size());
+  let answers_end = skip_resource_records(answers_start, header.answer_count, data, limits)?;
+  let name_servers_end =
+    skip_resource_records(answers_end, header.name_server_count, data, limits)?;
+  let message_length =
+    skip_resource_records(name_servers_end, header.additional_count, data, limits)?;
+  if message_length < data.len() && !options.allow_trailing_data {
+    return Err(ParseError::MessageError(
+      ErrorCode::TrailingData,
+      format!(
+        "Message ends at {} but data is {} bytes long",
+        message_length,
+        data.len()
+      ),
+    ));
+  }
+
+  Ok(LazyMessage {
+    header,
+    queries,
+    data,
+    options: options.clone(),
+    ranges: [
+      answers_start..answers_end,
+      answers_end..name_servers_end,
+      name_servers_end..message_length,
+    ],
+    label_store,
+    sections: vec![],
   })
 }
 
 mod test {
+
+  #[test]
+  fn parse_lazy() {
+    let data = [
+      0, 0, 132, 0, 0, 0, 0, 1, 0, 0, 0, 1, // header
+      4, 95, 104, 97, 112, 4, 95, 116, 99, 112, 5, 108, 111, 99, 97, 108, 0, 0, 12, 0, 1, 0, 0, 17,
+      148, 0, 9, 6, 66, 114, 105, 100, 103, 101, 192, 12, // ptr
+      192, 39, 0, 16, 0, 1, 0, 0, 17, 148, 0, 5, 4, 99, 35, 61, 50, // txt
+    ];
+    let mut message = super::parse_lazy(&data).unwrap();
+    assert_eq!(
+      12..48,
+      message.section_range(crate::shared::Section::Answer)
+    );
+    assert_eq!(
+      48..48,
+      message.section_range(crate::shared::Section::Authority)
+    );
+    assert_eq!(
+      48..data.len(),
+      message.section_range(crate::shared::Section::Additional)
+    );
+
+    let additional = message.additional_records().unwrap();
+    assert_eq!("Bridge._hap._tcp.local", additional[0].name);
+    assert_eq!(
+      crate::resource_record::ResourceRecordData::TXT(vec![b"c#=2".to_vec()]),
+      additional[0].resource_record_data
+    );
+    assert_eq!(1, message.answers().unwrap().len());
+  }
+
   #[test]
-  fn test_esp_packet() {
-    let data = &[
-      0, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 2, 10, 69, 83, 80, 95, 56, 48, 57, 57, 57, 50, 8, 95, 102,
-      97, 98, 114, 108, 121, 110, 4, 95, 117, 100, 112, 5, 108, 111, 99, 97, 108, 0, 0, 255, 128,
-      1, 192, 48, 0, 12, 0, 1, 0, 0, 17, 148, 0, 2, 192, 79, 192, 79, 0, 33, 0, 1, 0, 0, 17, 148,
-      0, 8, 0, 0, 0, 0, 19, 136, 192, 129, 192, 79, 0, 16, 0, 1, 0, 0, 17, 148, 0, 0, 192, 129, 0,
-      1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 43,
+  fn parse_lazy_question_section() {
+    let data = [
+      0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, // header
+      7, 112, 114, 105, 110, 116, 101, 114, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1, // query
     ];
+    let mut message = super::parse_lazy(&data).unwrap();
+    assert_eq!(
+      12..data.len(),
+      message.section_range(crate::shared::Section::Question)
+    );
+    assert_eq!("printer.local", message.queries[0].name);
+    match message.section(crate::shared::Section::Question) {
+      Err(super::ParseError::MessageError(..)) => {}
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_lazy_with_invalid_section() {
+    let data = [
+      0, 0, 132, 0, 0, 0, 0, 1, 0, 0, 0, 0, // header
+      1, 97, 0, 0, 1, 0, 1, 0, 0, 0, 120, 0, 2, 192, 168, // short address
+    ];
+    let mut message = super::parse_lazy(&data).unwrap();
+    for _ in 0..2 {
+      assert!(message.answers().is_err());
+      assert!(message.label_store.is_empty());
+    }
+  }
 
-    let result = super::parse(data);
+  #[test]
+  fn parse_with_limits() {
+    let data = [
+      0, 0, 132, 0, 0, 0, 0, 1, 0, 0, 0, 0, // header
+      1, 97, 1, 98, 1, 99, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168,
+      1, 10,
+    ];
+    let limits = |max_labels, max_records| crate::shared::ParseOptions {
+      limits: crate::shared::Limits {
+        max_labels,
+        max_records,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    assert!(super::parse_with_options(&data, &limits(4, 1)).is_ok());
+
+    let error = super::parse_with_options(&data, &limits(3, 1)).unwrap_err();
+    assert_eq!(Some((crate::shared::Section::Answer, 0)), error.section());
+    assert_eq!("E_LIMIT_EXCEEDED", error.code());
+
+    match super::parse_with_options(&data, &limits(4, 0)) {
+      Err(super::ParseError::LimitError(..)) => {}
+      _ => panic!(),
+    }
+
+    let options = crate::shared::ParseOptions {
+      limits: crate::shared::Limits {
+        max_message_size: data.len() - 1,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    for error in &[
+      super::parse_with_options(&data, &options).unwrap_err(),
+      super::parse_lazy_with_options(&data, &options)
+        .err()
+        .unwrap(),
+    ] {
+      assert_eq!(None, error.section());
+      assert_eq!("E_LIMIT_EXCEEDED", error.code());
+    }
+  }
+
+  #[test]
+  fn peek() {
+    let data = [
+      0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, // header
+      1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1, // query
+      255, 255, 255, // garbage
+    ];
+    let (header, query) = super::Message::peek(&data, &Default::default()).unwrap();
+    assert_eq!(
+      crate::header::QueryOrResponse::Query,
+      header.query_or_response
+    );
+    assert_eq!("a.local", query.unwrap().name);
+    assert!(super::parse(&data).is_err());
+
+    let (_, query) =
+      super::Message::peek(&[0, 0, 132, 0, 0, 0, 0, 1, 0, 0, 0, 0], &Default::default()).unwrap();
+    assert!(query.is_none());
+  }
+
+  #[test]
+  fn peek_with_unicast_response_bit() {
+    let data = [
+      0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, // header
+      1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 128, 1, // query
+    ];
+    let (_, query) = super::Message::peek(&data, &Default::default()).unwrap();
+    let query = query.unwrap();
+    assert_eq!(
+      crate::query::QuestionResponseType::QM,
+      *query.q_response_type()
+    );
+    assert_eq!(
+      crate::query::QClass::Class(crate::shared::Class::Other(32769)),
+      *query.q_class()
+    );
+
+    let permissive = crate::shared::Profile::PermissiveMdns.into();
+    let (_, query) = super::Message::peek(&data, &permissive).unwrap();
+    let query = query.unwrap();
+    assert_eq!(
+      crate::query::QuestionResponseType::QU,
+      *query.q_response_type()
+    );
+    assert_eq!(
+      crate::query::QClass::Class(crate::shared::Class::IN),
+      *query.q_class()
+    );
+  }
+
+  #[test]
+  fn encoded_len() {
+    let data = [
+      0, 0, 132, 0, 0, 1, 0, 1, 0, 0, 0, 0, // header
+      1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1, // query
+      192, 12, 0, 16, 0, 1, 0, 0, 0, 120, 0, 6, 5, 104, 101, 108, 108, 111, // answer
+    ];
+    let message = super::parse(&data).unwrap();
+    assert_eq!(data.len() + 7, message.encoded_len());
+    assert_eq!(6, message.answers[0].resource_record_data.encoded_len());
+  }
+
+  #[test]
+  fn parse_with_trailing_data() {
+    let data = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255];
+    match super::parse(&data) {
+      Err(super::ParseError::MessageError(..)) => {}
+      _ => panic!(),
+    }
+
+    let options = crate::shared::ParseOptions::from(crate::shared::Profile::PermissiveMdns);
+    assert!(super::parse_with_options(&data, &options).is_ok());
+  }
+
+  #[test]
+  fn parse_stream() {
+    let mut data = vec![0, 1, 132, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    data.extend(&[
+      0, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1,
+    ]);
+    data.extend(&[0, 3, 0, 0]);
+
+    let mut stream = super::parse_stream(&data);
+    let (first, length) = stream.next().unwrap().unwrap();
+    assert_eq!((1, 12), (first.header.id, length));
+    let (second, length) = stream.next().unwrap().unwrap();
+    assert_eq!((2, 25), (second.header.id, length));
+    assert!(stream.next().unwrap().is_err());
+    assert_eq!(37, stream.offset());
+    assert!(stream.next().is_none());
+
+    stream.resume_at(data.len());
+    assert!(stream.next().is_none());
+  }
+
+  #[test]
+  fn parse_stream_and_skip_bad_message() {
+    let mut data = vec![0, 1, 132, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    data.extend(&[
+      0, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 63, 97, 0, 1, 0, 1, // truncated label
+    ]);
+    data.extend(&[0, 3, 132, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+    let mut stream = super::parse_stream(&data);
+    assert_eq!(1, stream.next().unwrap().unwrap().0.header.id);
+    assert!(stream.next().unwrap().is_err());
+    assert_eq!(12, stream.offset());
+    assert!(stream.next().is_none());
+
+    stream.skip_bytes(18);
+    let (third, length) = stream.next().unwrap().unwrap();
+    assert_eq!((3, 12), (third.header.id, length));
+    assert!(stream.next().is_none());
+  }
+
+  #[test]
+  fn parse_with_retained_raw() {
+    let data = [
+      0, 1, 132, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1, 192,
+      12, 0, 1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 2,
+    ];
+    let options = crate::shared::ParseOptions {
+      retain_raw: true,
+      ..Default::default()
+    };
+    let message = super::parse_with_options(&data, &options).unwrap();
+    assert_eq!(12..25, message.queries[0].range);
+    assert_eq!(25..41, message.answers[0].range);
+    assert_eq!(
+      Some(&[192, 168, 1, 2][..]),
+      message.bytes(&message.answers[0].data_range())
+    );
+    assert_eq!(None, super::parse(&data).unwrap().bytes(&(0..12)));
+  }
+
+  #[test]
+  fn parse_with_section_error() {
+    let data = [
+      0, 0, 132, 0, 0, 0, 0, 2, 0, 0, 0, 0, 1, 97, 0, 0, 1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1,
+      2, 1, 98, 0, 0, 1, 0, 1, 0, 0, 0, 120, 0, 2, 192, 168,
+    ];
+    let error = super::parse(&data).unwrap_err();
+    assert_eq!(Some((super::Section::Answer, 1)), error.section());
+    match error.root_cause() {
+      super::ParseError::ResourceRecordError(..) => {}
+      _ => panic!(),
+    }
   }
 }
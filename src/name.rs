@@ -0,0 +1,233 @@
+use crate::shared::{encode_name, ErrorCode, ParseError};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+#[derive(Clone, Debug)]
+pub struct Name(String);
+
+impl Name {
+  pub fn new(name: &str) -> Self {
+    Name(name.to_owned())
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+
+  pub fn eq_preserving_case(&self, other: &Name) -> bool {
+    self.canonical_bytes(false) == other.canonical_bytes(false)
+  }
+
+  pub fn from_hostname(hostname: &str) -> Result<Self, ParseError> {
+    let name = Name::new(hostname);
+    match name.is_hostname() {
+      true => Ok(name),
+      false => Err(ParseError::NameError(
+        ErrorCode::InvalidName,
+        format!("Invalid hostname: {}", hostname),
+      )),
+    }
+  }
+
+  pub fn is_hostname(&self) -> bool {
+    let name = self.0.strip_suffix('.').unwrap_or(&self.0);
+    !name.is_empty()
+      && name.len() <= 253
+      && name.split('.').all(|label| {
+        !label.is_empty()
+          && label.len() <= 63
+          && !label.starts_with('-')
+          && !label.ends_with('-')
+          && label
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+      })
+  }
+
+  pub fn to_local(&self) -> Name {
+    let name = self.0.strip_suffix('.').unwrap_or(&self.0);
+    let host = name.split('.').next().unwrap_or(name);
+    Name(format!("{}.local", host))
+  }
+
+  pub(crate) fn canonical_bytes(&self, fold_case: bool) -> Vec<u8> {
+    let mut bytes = encode_name(&self.0).unwrap_or_else(|_| self.0.as_bytes().to_vec());
+    if fold_case {
+      bytes.make_ascii_lowercase();
+    }
+    bytes
+  }
+
+  pub fn is_subdomain_of(&self, other: &Name) -> bool {
+    self
+      .canonical_labels()
+      .starts_with(&other.canonical_labels())
+  }
+
+  fn canonical_labels(&self) -> Vec<Vec<u8>> {
+    let bytes = self.canonical_bytes(true);
+    let mut labels = vec![];
+    let mut offset = 0;
+    while offset < bytes.len() && bytes[offset] != 0 {
+      let end = (offset + 1 + bytes[offset] as usize).min(bytes.len());
+      labels.push(bytes[offset + 1..end].to_vec());
+      offset = end;
+    }
+    labels.reverse();
+    labels
+  }
+}
+
+impl PartialEq for Name {
+  fn eq(&self, other: &Name) -> bool {
+    self.canonical_bytes(true) == other.canonical_bytes(true)
+  }
+}
+
+impl Eq for Name {}
+
+impl Hash for Name {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.canonical_bytes(true).hash(state);
+  }
+}
+
+impl PartialOrd for Name {
+  fn partial_cmp(&self, other: &Name) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Name {
+  fn cmp(&self, other: &Name) -> Ordering {
+    self.canonical_labels().cmp(&other.canonical_labels())
+  }
+}
+
+impl fmt::Display for Name {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl std::str::FromStr for Name {
+  type Err = ParseError;
+
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    encode_name(name)?;
+    Ok(Name::new(name))
+  }
+}
+
+impl From<String> for Name {
+  fn from(name: String) -> Self {
+    Name(name)
+  }
+}
+
+impl From<Name> for String {
+  fn from(name: Name) -> Self {
+    name.0
+  }
+}
+
+impl From<&str> for Name {
+  fn from(name: &str) -> Self {
+    Name::new(name)
+  }
+}
+
+mod test {
+
+  #[test]
+  fn eq_ignores_case() {
+    let lower = super::Name::new("printer.local");
+    let upper = super::Name::new("PRINTER.Local");
+    assert_eq!(lower, upper);
+    assert_eq!("PRINTER.Local", upper.to_string());
+    assert!(!lower.eq_preserving_case(&upper));
+    assert!(lower.eq_preserving_case(&super::Name::new("printer.local.")));
+  }
+
+  #[test]
+  fn eq_decodes_escapes() {
+    assert_eq!(
+      super::Name::new("\\080rinter.local"),
+      super::Name::new("printer.local")
+    );
+    assert_ne!(
+      super::Name::new("a\\.b.local"),
+      super::Name::new("a.b.local")
+    );
+  }
+
+  #[test]
+  fn is_subdomain_of() {
+    let name = super::Name::new("www.Example.com");
+    assert!(name.is_subdomain_of(&super::Name::new("example.COM")));
+    assert!(name.is_subdomain_of(&super::Name::new("www.example.com.")));
+    assert!(name.is_subdomain_of(&super::Name::new(".")));
+    assert!(!name.is_subdomain_of(&super::Name::new("ample.com")));
+    assert!(!name.is_subdomain_of(&super::Name::new("example.org")));
+  }
+
+  #[test]
+  fn from_str() {
+    assert_eq!(
+      super::Name::new("printer.local"),
+      "printer.local".parse().unwrap()
+    );
+    assert!("a..local".parse::<super::Name>().is_err());
+  }
+
+  #[test]
+  fn is_hostname() {
+    let test_data = [
+      ("printer", true),
+      ("Johns-MacBook.local.", true),
+      ("printer-", false),
+      ("-printer", false),
+      ("my_printer", false),
+      ("Living Room", false),
+      ("a..local", false),
+      ("", false),
+    ];
+    for (name, expected) in &test_data {
+      assert_eq!(*expected, super::Name::new(name).is_hostname(), "{}", name);
+    }
+    assert!(super::Name::from_hostname(&"a".repeat(64)).is_err());
+  }
+
+  #[test]
+  fn to_local() {
+    let name = super::Name::from_hostname("mybox.corp.example").unwrap();
+    assert_eq!("mybox.local", String::from(name.to_local()));
+    assert_eq!(
+      "printer.local",
+      super::Name::new("printer.local.").to_local().as_str()
+    );
+  }
+
+  #[test]
+  fn hash_ignores_case() {
+    let mut names = std::collections::HashSet::new();
+    names.insert(super::Name::new("printer.local"));
+    assert!(names.contains(&super::Name::new("Printer.LOCAL")));
+  }
+
+  #[test]
+  fn cmp_uses_canonical_order() {
+    let mut names = [
+      super::Name::new("tv.local"),
+      super::Name::new("Printer.local"),
+      super::Name::new("local"),
+      super::Name::new("a.example"),
+    ];
+    names.sort();
+    assert_eq!(
+      vec!["a.example", "local", "Printer.local", "tv.local"],
+      names.iter().map(|n| n.as_str()).collect::<Vec<&str>>()
+    );
+  }
+}
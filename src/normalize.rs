@@ -0,0 +1,141 @@
+use crate::message::Message;
+use crate::name::Name;
+use crate::query::Query;
+use crate::resource_record::{ResourceRecord, ResourceRecordData};
+use crate::shared::{name_to_labels, Label};
+
+fn detached_labels(name: &str) -> Vec<Label> {
+  name_to_labels(0, name).unwrap_or_default()
+}
+
+fn lowercase_data(data: &mut ResourceRecordData) {
+  match data {
+    ResourceRecordData::PTR(name) | ResourceRecordData::NS(name) => name.make_ascii_lowercase(),
+    ResourceRecordData::SRV(srv) => srv.target.make_ascii_lowercase(),
+    ResourceRecordData::SOA(soa) => {
+      soa.mname.make_ascii_lowercase();
+      soa.rname.make_ascii_lowercase();
+    }
+    _ => {}
+  }
+}
+
+fn normalize_queries(queries: &mut Vec<Query>) {
+  queries.iter_mut().for_each(|q| {
+    q.name.make_ascii_lowercase();
+    q.values = detached_labels(&q.name);
+    q.range = 0..q.size();
+  });
+  queries.sort_by_cached_key(|q| (Name::new(&q.name), q.type_and_class_codes()));
+  queries.dedup_by(|a, b| a.name == b.name && a.type_and_class_codes() == b.type_and_class_codes());
+}
+
+fn normalize_records(records: &mut Vec<ResourceRecord>) {
+  records.iter_mut().for_each(|r| {
+    r.name.make_ascii_lowercase();
+    lowercase_data(&mut r.resource_record_data);
+    r.values = detached_labels(&r.name);
+    r.range = 0..r.size();
+  });
+  records.sort_by_cached_key(|r| {
+    (
+      Name::new(&r.name),
+      r.resource_record_type.code(),
+      r.class.code(),
+      r.resource_record_data.canonical_bytes(),
+    )
+  });
+  records.dedup_by(|duplicate, kept| {
+    let same = duplicate.name == kept.name
+      && duplicate.resource_record_type == kept.resource_record_type
+      && duplicate.class == kept.class
+      && duplicate.resource_record_data == kept.resource_record_data;
+    if same {
+      kept.ttl = kept.ttl.max(duplicate.ttl);
+      kept.cache_flush |= duplicate.cache_flush;
+    }
+    same
+  });
+}
+
+impl Message {
+  pub fn normalize(&mut self) {
+    normalize_queries(&mut self.queries);
+    normalize_records(&mut self.answers);
+    normalize_records(&mut self.name_servers);
+    normalize_records(&mut self.additional_records);
+
+    self.header.question_count = self.queries.len() as u16;
+    self.header.answer_count = self.answers.len() as u16;
+    self.header.name_server_count = self.name_servers.len() as u16;
+    self.header.additional_count = self.additional_records.len() as u16;
+    self.raw = None;
+  }
+}
+
+#[cfg(test)]
+mod test {
+
+  #[test]
+  fn normalize() {
+    let mut message = crate::test_support::response(&[
+      "TV.local. 120 IN A 192.168.1.20",
+      "Printer.local. 120 IN PTR Host.LOCAL.",
+      "tv.local. 4500 IN A 192.168.1.20",
+      "printer.local. 120 IN A 192.168.1.10",
+    ]);
+    message.normalize();
+
+    assert_eq!(3, message.header.answer_count);
+    assert_eq!(
+      vec![
+        ("printer.local", 1, 120),
+        ("printer.local", 12, 120),
+        ("tv.local", 1, 4500)
+      ],
+      message
+        .answers
+        .iter()
+        .map(|r| (r.name.as_str(), r.resource_record_type.code(), r.ttl))
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(
+      crate::resource_record::ResourceRecordData::PTR("host.local".to_owned()),
+      message.answers[1].resource_record_data
+    );
+    assert_eq!(
+      "tv.local",
+      crate::shared::labels_to_name(&message.answers[2].values)
+    );
+  }
+
+  #[test]
+  fn normalize_parsed_message() {
+    let data = [
+      0, 0, 132, 0, 0, 0, 0, 2, 0, 0, 0, 0, // header
+      1, 66, 5, 108, 111, 99, 97, 108, 0, 0, 1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1,
+      3, // B.local
+      1, 97, 192, 14, 0, 1, 0, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 2, // a.local
+    ];
+    let options = crate::shared::ParseOptions {
+      retain_raw: true,
+      ..Default::default()
+    };
+    let mut message = crate::message::parse_with_options(&data, &options).unwrap();
+    message.normalize();
+
+    assert!(message.raw.is_none());
+    assert_eq!(
+      vec![(0..23, 3), (0..23, 3)],
+      message
+        .answers
+        .iter()
+        .map(|r| (r.range.clone(), r.values.len()))
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(
+      "b.local",
+      crate::shared::labels_to_name(&message.answers[1].values)
+    );
+  }
+}
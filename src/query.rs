@@ -1,39 +1,94 @@
 use crate::header::Header;
+use crate::iana::{class_code, type_code};
+use crate::name::Name;
+use crate::resource_record::{parse_resource_record_type, RecordType, ResourceRecord};
 use crate::shared::{
-  extract_domain_name, parse_class, parse_name, parse_type, Class, Label, ParseError, Type,
+  encoded_name_len, extract_domain_name, labels_to_name, name_to_labels, parse_class, parse_name,
+  Class, ErrorCode, Label, ParseError, ParseOptions, Section,
 };
+use std::ops::Range;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum QType {
-  Type(Type),
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QType {
+  Type(RecordType),
   AXFR,
   MAILB,
   MAILA,
   Any,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum QClass {
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QClass {
   Any,
   Class(Class),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Query {
   pub values: Vec<Label>,
   pub name: String,
+  pub range: Range<usize>,
   q_response_type: QuestionResponseType,
   q_type: QType,
   q_class: QClass,
 }
 
-#[derive(PartialEq, Eq, Debug)]
-enum QuestionResponseType {
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum QuestionResponseType {
   QU,
   QM,
 }
 
 impl Query {
+  pub fn q_type(&self) -> &QType {
+    &self.q_type
+  }
+
+  pub fn q_class(&self) -> &QClass {
+    &self.q_class
+  }
+
+  pub fn q_response_type(&self) -> &QuestionResponseType {
+    &self.q_response_type
+  }
+
+  pub(crate) fn type_and_class_codes(&self) -> (u16, u16) {
+    let q_type = match &self.q_type {
+      QType::Type(t) => t.code(),
+      QType::AXFR => 252,
+      QType::MAILB => 253,
+      QType::MAILA => 254,
+      QType::Any => 255,
+    };
+    let q_class = match &self.q_class {
+      QClass::Any => 255,
+      QClass::Class(c) => c.code(),
+    };
+    (q_type, q_class)
+  }
+
+  pub fn matches(&self, record: &ResourceRecord) -> bool {
+    let record_type = record.resource_record_type.code();
+    let type_matches = match &self.q_type {
+      QType::Any => true,
+      QType::AXFR => false,
+      QType::MAILB => matches!(record_type, 7..=9),
+      QType::MAILA => matches!(record_type, 3 | 4),
+      QType::Type(t) => t.code() == record_type,
+    };
+    let class_matches = match &self.q_class {
+      QClass::Any => true,
+      QClass::Class(c) => c.code() & 0x7fff == record.class.code() & 0x7fff,
+    };
+
+    type_matches && class_matches && Name::new(&self.name) == Name::new(&record.name)
+  }
+
+  pub fn encoded_len(&self) -> usize {
+    encoded_name_len(&self.name) + 4
+  }
+
   pub fn size(&self) -> usize {
     let q_type_size = 2;
     let q_class_size = 2;
@@ -49,36 +104,87 @@ pub fn parse_query(
   label_store: &mut Vec<Label>,
   offset: usize,
   data: &[u8],
+  options: &ParseOptions,
 ) -> Result<Query, ParseError> {
-  let values = parse_name(offset, data)?;
+  let values = parse_name(offset, data, &options.limits)?;
   values.iter().for_each(|v| label_store.push(v.clone()));
-  let name = extract_domain_name(label_store, &values);
+  let name = extract_domain_name(label_store, &values)?;
+  let name_end = values.iter().fold(offset, |sum, l| sum + l.size());
 
-  let offset = values.iter().fold(0, |sum, l| sum + l.size());
-
-  if data.len() < offset + 4 {
+  if data.len() < name_end + 4 {
     return Err(ParseError::QueryError(
+      ErrorCode::TruncatedQuery,
       "Data not long enough for query".to_owned(),
     ));
   }
 
-  let mut q_type_data: [u8; 2] = [0; 2];
-  q_type_data.copy_from_slice(&data[offset..offset + 2]);
-  let (q_response_type, q_type) = parse_q_type(q_type_data);
-
-  let mut q_class_data: [u8; 2] = [0; 2];
-  q_class_data.copy_from_slice(&data[offset + 2..offset + 4]);
-  let q_class = parse_q_class(q_class_data);
+  let q_type = parse_q_type([data[name_end], data[name_end + 1]]);
+  let (q_response_type, q_class) =
+    parse_q_class_with_top_bit([data[name_end + 2], data[name_end + 3]], options);
 
   Ok(Query {
     name,
     values,
+    range: offset..name_end + 4,
     q_response_type,
     q_type,
     q_class,
   })
 }
 
+impl std::str::FromStr for Query {
+  type Err = ParseError;
+
+  fn from_str(line: &str) -> Result<Self, Self::Err> {
+    let mut tokens = line.split_whitespace();
+    let values = match tokens.next() {
+      Some(name) => name_to_labels(0, name)?,
+      None => {
+        return Err(ParseError::QueryError(
+          ErrorCode::InvalidPresentation,
+          "Query is missing a name".to_owned(),
+        ))
+      }
+    };
+    let mut q_type = None;
+    let mut q_class = None;
+
+    for token in tokens {
+      match (type_code(token), class_code(token)) {
+        (Some(code), _) if q_type.is_none() => q_type = Some(parse_q_type(code.to_be_bytes())),
+        (_, Some(code)) if q_class.is_none() => q_class = Some(parse_q_class(code.to_be_bytes())),
+        _ => {
+          return Err(ParseError::QueryError(
+            ErrorCode::InvalidPresentation,
+            format!("Unexpected query field: {}", token),
+          ))
+        }
+      }
+    }
+
+    let q_type = match q_type {
+      Some(QType::Type(RecordType::Other(0))) | None => {
+        return Err(ParseError::QueryError(
+          ErrorCode::InvalidPresentation,
+          format!("Query is missing a type: {}", line),
+        ))
+      }
+      Some(q_type) => q_type,
+    };
+    let q_class = q_class.unwrap_or(QClass::Class(Class::IN));
+    let size = values.iter().fold(4, |sum, l| sum + l.size());
+
+    Ok(Query {
+      name: labels_to_name(&values),
+      values,
+      range: 0..size,
+      q_response_type: QuestionResponseType::QM,
+      q_type,
+      q_class,
+    })
+  }
+}
+
 fn parse_q_class(data: [u8; 2]) -> QClass {
   match u16::from_be_bytes([data[0], data[1]]) {
     255 => QClass::Any,
@@ -86,6 +192,19 @@ fn parse_q_class(data: [u8; 2]) -> QClass {
   }
 }
 
+fn parse_q_class_with_top_bit(
+  data: [u8; 2],
+  options: &ParseOptions,
+) -> (QuestionResponseType, QClass) {
+  match parse_q_response_type(data[0]) {
+    QuestionResponseType::QU if options.allow_class_top_bit => (
+      QuestionResponseType::QU,
+      parse_q_class([data[0] & 0b01111111, data[1]]),
+    ),
+    _ => (QuestionResponseType::QM, parse_q_class(data)),
+  }
+}
+
 fn parse_q_response_type(data: u8) -> QuestionResponseType {
   if (0b10000000 & data) == 0b10000000 {
     return QuestionResponseType::QU;
@@ -93,19 +212,14 @@ fn parse_q_response_type(data: u8) -> QuestionResponseType {
   QuestionResponseType::QM
 }
 
-fn parse_q_type(data: [u8; 2]) -> (QuestionResponseType, QType) {
-  let q_type = u16::from_be_bytes([(0b01111111 & data[0]), data[1]]);
-  let response_type = parse_q_response_type(data[0]);
-  (
-    response_type,
-    match q_type {
-      252 => QType::AXFR,
-      253 => QType::MAILB,
-      254 => QType::MAILA,
-      255 => QType::Any,
-      _ => QType::Type(parse_type(data)),
-    },
-  )
+fn parse_q_type(data: [u8; 2]) -> QType {
+  match u16::from_be_bytes(data) {
+    252 => QType::AXFR,
+    253 => QType::MAILB,
+    254 => QType::MAILA,
+    255 => QType::Any,
+    _ => QType::Type(parse_resource_record_type(data)),
+  }
 }
 
 pub fn parse_queries(
@@ -113,12 +227,14 @@ pub fn parse_queries(
   offset: usize,
   header: &Header,
   data: &[u8],
+  options: &ParseOptions,
 ) -> Result<Vec<Query>, ParseError> {
   let mut queries = vec![];
   let mut current_offset = offset;
-  for _ in 0..header.question_count {
-    let query = parse_query(label_store, current_offset, data)?;
-    current_offset += query.size();
+  for index in 0..header.question_count as usize {
+    let query = parse_query(label_store, current_offset, data, options)
+      .map_err(|e| ParseError::SectionError(Section::Question, index, Box::new(e)))?;
+    current_offset = query.range.end;
     queries.push(query);
   }
   Ok(queries)
@@ -133,22 +249,102 @@ mod test {
       ([0, 253], super::QType::MAILB),
       ([0, 252], super::QType::AXFR),
       ([0, 255], super::QType::Any),
-      ([0, 1], super::QType::Type(super::Type::A)),
-      ([0, 0], super::QType::Type(super::Type::Invalid)),
+      ([0, 1], super::QType::Type(super::RecordType::A)),
+      ([0, 0], super::QType::Type(super::RecordType::Other(0))),
     ];
 
     for td in &test_data {
       let result = super::parse_q_type(td.0);
-      assert_eq!((super::QuestionResponseType::QM, td.1), result);
+      assert_eq!(td.1, result);
+    }
+  }
+
+  #[test]
+  fn parse_q_type_for_other() {
+    assert_eq!(
+      super::QType::Type(super::RecordType::SRV),
+      super::parse_q_type([0, 33])
+    );
+  }
+
+  #[test]
+  fn query_from_str() {
+    let test_data = [
+      ("example.local IN SRV", (33, 1)),
+      ("_hap._tcp.local PTR", (12, 1)),
+      ("printer.local A ANY", (1, 255)),
+    ];
+    for (line, codes) in &test_data {
+      let query = line.parse::<super::Query>().unwrap();
+      assert_eq!(*codes, query.type_and_class_codes());
+    }
+    assert_eq!(
+      "_hap._tcp.local",
+      "_hap._tcp.local. PTR".parse::<super::Query>().unwrap().name
+    );
+  }
+
+  #[test]
+  fn query_from_str_and_fail() {
+    for line in &[
+      "",
+      "printer.local",
+      "printer.local IN",
+      "printer.local A BOGUS",
+    ] {
+      match line.parse::<super::Query>() {
+        Err(super::ParseError::QueryError(..)) => {}
+        _ => panic!(),
+      }
+    }
+  }
+
+  #[test]
+  fn accessors() {
+    let query = "printer.local CH AAAA".parse::<super::Query>().unwrap();
+    assert_eq!(&super::QType::Type(super::RecordType::AAAA), query.q_type());
+    assert_eq!(&super::QClass::Class(super::Class::CH), query.q_class());
+    assert_eq!(&super::QuestionResponseType::QM, query.q_response_type());
+  }
+
+  #[test]
+  fn matches() {
+    let record =
+      crate::resource_record::parse_resource_record_line("Printer.local. 120 IN A 192.168.1.10")
+        .unwrap();
+    let test_data = [
+      ("printer.local A", true),
+      ("PRINTER.LOCAL. IN A", true),
+      ("printer.local ANY", true),
+      ("printer.local A ANY", true),
+      ("printer.local AAAA", false),
+      ("printer.local CH A", false),
+      ("scanner.local A", false),
+    ];
+    for (line, expected) in &test_data {
+      let query = line.parse::<super::Query>().unwrap();
+      assert_eq!(*expected, query.matches(&record), "{}", line);
     }
   }
 
+  #[test]
+  fn matches_parsed_response() {
+    let data = [
+      0, 0, 132, 0, 0, 1, 0, 1, 0, 0, 0, 0, // header
+      7, 112, 114, 105, 110, 116, 101, 114, 5, 108, 111, 99, 97, 108, 0, 0, 1, 128,
+      1, // printer.local A QU
+      192, 12, 0, 1, 128, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 10, // printer.local A flush
+    ];
+    let message = crate::message::parse(&data).unwrap();
+    assert!(message.queries[0].matches(&message.answers[0]));
+  }
+
   #[test]
   fn parse_q_class() {
     let test_data = [
       ([0, 255], super::QClass::Any),
       ([0, 1], super::QClass::Class(super::Class::IN)),
-      ([0, 5], super::QClass::Class(super::Class::Invalid)),
+      ([0, 5], super::QClass::Class(super::Class::Other(5))),
     ];
 
     for td in &test_data {
@@ -157,6 +353,78 @@ mod test {
     }
   }
 
+  #[test]
+  fn parse_q_class_with_top_bit() {
+    let permissive = crate::shared::Profile::PermissiveMdns.into();
+    assert_eq!(
+      (
+        super::QuestionResponseType::QU,
+        super::QClass::Class(super::Class::IN)
+      ),
+      super::parse_q_class_with_top_bit([128, 1], &permissive)
+    );
+    assert_eq!(
+      (
+        super::QuestionResponseType::QM,
+        super::QClass::Class(super::Class::Other(32769))
+      ),
+      super::parse_q_class_with_top_bit([128, 1], &Default::default())
+    );
+  }
+
+  #[test]
+  fn parse_queries() {
+    let data = [
+      0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, // header
+      4, 95, 104, 97, 112, 4, 95, 116, 99, 112, 5, 108, 111, 99, 97, 108, 0, 0, 12, 128,
+      1, // _hap._tcp.local PTR QU
+      6, 66, 114, 105, 100, 103, 101, 192, 12, 0, 33, 0, 1, // Bridge._hap._tcp.local SRV
+      6, 98, 114, 105, 100, 103, 101, 192, 22, 0, 28, 0, 1, // bridge.local AAAA
+    ];
+    let header = crate::header::parse_header(&data).unwrap();
+    let options = crate::shared::Profile::PermissiveMdns.into();
+    let queries = super::parse_queries(&mut vec![], 12, &header, &data, &options).unwrap();
+
+    assert_eq!(
+      vec![
+        ("_hap._tcp.local", (12, 1), 12..33),
+        ("Bridge._hap._tcp.local", (33, 1), 33..46),
+        ("bridge.local", (28, 1), 46..59),
+      ],
+      queries
+        .iter()
+        .map(|q| (q.name.as_str(), q.type_and_class_codes(), q.range.clone()))
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(
+      &super::QuestionResponseType::QU,
+      queries[0].q_response_type()
+    );
+    assert_eq!(
+      &super::QuestionResponseType::QM,
+      queries[1].q_response_type()
+    );
+  }
+
+  #[test]
+  fn parse_queries_with_strict_profile() {
+    let data = [
+      0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, // header
+      1, 97, 5, 108, 111, 99, 97, 108, 0, 0, 1, 128, 1, // a.local A QU
+    ];
+    let header = crate::header::parse_header(&data).unwrap();
+    let options = crate::shared::Profile::Strict.into();
+    let queries = super::parse_queries(&mut vec![], 12, &header, &data, &options).unwrap();
+    assert_eq!(
+      &super::QuestionResponseType::QM,
+      queries[0].q_response_type()
+    );
+    assert_eq!(
+      &super::QClass::Class(super::Class::Other(0x8001)),
+      queries[0].q_class()
+    );
+  }
+
   #[test]
   fn parse_q_response_type_for_unicast() {
     let data = 0b10000000;
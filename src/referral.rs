@@ -0,0 +1,134 @@
+use crate::message::Message;
+use crate::name::Name;
+use crate::resource_record::{RecordType, ResourceRecordData};
+use std::net::IpAddr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Referral {
+  pub zone: Name,
+  pub nameservers: Vec<(Name, Vec<IpAddr>)>,
+}
+
+impl Message {
+  pub fn referral(&self) -> Option<Referral> {
+    let has_soa = self
+      .name_servers
+      .iter()
+      .any(|r| r.resource_record_type == RecordType::SOA);
+    if !self.answers.is_empty() || has_soa {
+      return None;
+    }
+
+    let query_name = Name::new(&self.queries.first()?.name);
+    let delegations = self
+      .name_servers
+      .iter()
+      .filter_map(|r| match &r.resource_record_data {
+        ResourceRecordData::NS(target) => Some((Name::new(&r.name), Name::new(target))),
+        _ => None,
+      })
+      .filter(|(owner, _)| query_name.is_subdomain_of(owner))
+      .collect::<Vec<(Name, Name)>>();
+    let zone = delegations.first()?.0.clone();
+
+    let nameservers = delegations
+      .into_iter()
+      .filter(|(owner, _)| *owner == zone)
+      .fold(
+        vec![],
+        |mut nameservers: Vec<(Name, Vec<IpAddr>)>, (_, target)| {
+          if !nameservers.iter().any(|(n, _)| *n == target) {
+            let glue = self
+              .additional_records
+              .iter()
+              .filter(|r| Name::new(&r.name) == target)
+              .filter_map(|r| match r.resource_record_data {
+                ResourceRecordData::A(address) => Some(IpAddr::V4(address)),
+                ResourceRecordData::AAAA(address) => Some(IpAddr::V6(address)),
+                _ => None,
+              })
+              .collect();
+            nameservers.push((target, glue));
+          }
+          nameservers
+        },
+      );
+
+    Some(Referral { zone, nameservers })
+  }
+}
+
+#[cfg(test)]
+mod test {
+
+  #[test]
+  fn referral() {
+    let mut message = crate::test_support::message(
+      [128, 0],
+      &[],
+      &[
+        "example.com. 172800 IN NS a.iana-servers.net.",
+        "example.com. 172800 IN NS b.iana-servers.net.",
+      ],
+      &[
+        "a.iana-servers.net. 172800 IN A 199.43.135.53",
+        "A.IANA-SERVERS.NET. 172800 IN AAAA 2001:500:8f::53",
+      ],
+    );
+    message.queries.push("www.example.com A".parse().unwrap());
+    let referral = message.referral().unwrap();
+    assert_eq!(crate::name::Name::new("example.com"), referral.zone);
+    assert_eq!(
+      vec![
+        (
+          crate::name::Name::new("a.iana-servers.net"),
+          vec![
+            "199.43.135.53".parse().unwrap(),
+            "2001:500:8f::53".parse().unwrap()
+          ]
+        ),
+        (crate::name::Name::new("b.iana-servers.net"), vec![]),
+      ],
+      referral.nameservers
+    );
+  }
+
+  #[test]
+  fn referral_with_answers() {
+    let mut message = crate::test_support::message(
+      [128, 0],
+      &["example.com. 300 IN A 93.184.216.34"],
+      &["example.com. 172800 IN NS a.iana-servers.net."],
+      &[],
+    );
+    message.queries.push("example.com A".parse().unwrap());
+    assert_eq!(None, message.referral());
+  }
+
+  #[test]
+  fn referral_with_soa() {
+    let mut message = crate::test_support::message(
+      [128, 0],
+      &[],
+      &[
+        "example.com. 3600 IN SOA ns.example.com. admin.example.com. 1 7200 3600 1209600 300",
+        "example.com. 172800 IN NS a.iana-servers.net.",
+      ],
+      &[],
+    );
+    message.queries.push("www.example.com A".parse().unwrap());
+    assert_eq!(None, message.referral());
+  }
+
+  #[test]
+  fn referral_outside_query_name() {
+    let mut message = crate::test_support::message(
+      [128, 0],
+      &[],
+      &["example.com. 172800 IN NS a.iana-servers.net."],
+      &[],
+    );
+    message.queries.push("www.example.org A".parse().unwrap());
+    assert_eq!(None, message.referral());
+  }
+}
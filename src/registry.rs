@@ -0,0 +1,107 @@
+use crate::resource_record::{ResourceRecord, ResourceRecordData};
+use crate::shared::ParseError;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+pub trait CustomRdata: Debug {
+  fn encode(&self) -> Vec<u8>;
+  fn as_any(&self) -> &dyn Any;
+}
+
+type RdataDecoder = Box<dyn Fn(&[u8]) -> Result<Box<dyn CustomRdata>, ParseError>>;
+
+#[derive(Default)]
+pub struct RecordTypeRegistry {
+  decoders: HashMap<u16, RdataDecoder>,
+}
+
+impl RecordTypeRegistry {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn register<F>(&mut self, code: u16, decoder: F)
+  where
+    F: Fn(&[u8]) -> Result<Box<dyn CustomRdata>, ParseError> + 'static,
+  {
+    self.decoders.insert(code, Box::new(decoder));
+  }
+
+  pub fn is_registered(&self, code: u16) -> bool {
+    self.decoders.contains_key(&code)
+  }
+
+  pub fn decode(
+    &self,
+    record: &ResourceRecord,
+  ) -> Option<Result<Box<dyn CustomRdata>, ParseError>> {
+    let decoder = self.decoders.get(&record.resource_record_type.code())?;
+    match &record.resource_record_data {
+      ResourceRecordData::Other(data) => Some(decoder(data)),
+      _ => None,
+    }
+  }
+}
+
+mod test {
+
+  #[derive(Debug, PartialEq)]
+  struct Temperature(i16);
+
+  impl super::CustomRdata for Temperature {
+    fn encode(&self) -> Vec<u8> {
+      self.0.to_be_bytes().to_vec()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+      self
+    }
+  }
+
+  #[allow(dead_code)]
+  fn decode_temperature(data: &[u8]) -> Result<Box<dyn super::CustomRdata>, super::ParseError> {
+    match data {
+      [high, low] => Ok(Box::new(Temperature(i16::from_be_bytes([*high, *low])))),
+      _ => Err(super::ParseError::ResourceRecordError(
+        crate::shared::ErrorCode::DataLengthMismatch,
+        "Temperature needs 2 bytes".to_owned(),
+      )),
+    }
+  }
+
+  #[test]
+  fn decode() {
+    let mut registry = super::RecordTypeRegistry::new();
+    registry.register(65280, decode_temperature);
+
+    let record = crate::resource_record::parse_resource_record_line(
+      "sensor.local. 120 IN TYPE65280 \\# 2 00e1",
+    )
+    .unwrap();
+    let rdata = registry.decode(&record).unwrap().unwrap();
+    assert_eq!(
+      Some(&Temperature(225)),
+      rdata.as_any().downcast_ref::<Temperature>()
+    );
+    assert_eq!(vec![0, 225], rdata.encode());
+
+    let other =
+      crate::resource_record::parse_resource_record_line("sensor.local. 120 IN A 192.168.1.2")
+        .unwrap();
+    assert!(registry.decode(&other).is_none());
+  }
+
+  #[test]
+  fn decode_with_invalid_data() {
+    let mut registry = super::RecordTypeRegistry::new();
+    registry.register(65280, decode_temperature);
+    let record =
+      crate::resource_record::parse_resource_record_line("sensor.local. 120 IN TYPE65280 \\# 1 00")
+        .unwrap();
+    match registry.decode(&record) {
+      Some(Err(super::ParseError::ResourceRecordError(..))) => {}
+      _ => panic!(),
+    }
+  }
+}
@@ -0,0 +1,182 @@
+use std::net::IpAddr;
+
+const MAX_NAMESERVERS: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvConf {
+  pub nameservers: Vec<IpAddr>,
+  pub domain: Option<String>,
+  pub search: Vec<String>,
+  pub ndots: u8,
+  pub timeout: u32,
+  pub attempts: u32,
+  pub rotate: bool,
+  pub edns0: bool,
+}
+
+impl Default for ResolvConf {
+  fn default() -> Self {
+    ResolvConf {
+      nameservers: vec![],
+      domain: None,
+      search: vec![],
+      ndots: 1,
+      timeout: 5,
+      attempts: 2,
+      rotate: false,
+      edns0: false,
+    }
+  }
+}
+
+impl ResolvConf {
+  pub fn search_list(&self) -> Vec<String> {
+    match (&self.search, &self.domain) {
+      (search, _) if !search.is_empty() => search.clone(),
+      (_, Some(domain)) => vec![domain.clone()],
+      _ => vec![],
+    }
+  }
+
+  pub fn search_candidates(&self, name: &str) -> Vec<String> {
+    if name.ends_with('.') {
+      return vec![name.trim_end_matches('.').to_owned()];
+    }
+
+    let expanded = self
+      .search_list()
+      .into_iter()
+      .map(|domain| format!("{}.{}", name, domain.trim_end_matches('.')));
+    if name.matches('.').count() >= self.ndots as usize {
+      std::iter::once(name.to_owned()).chain(expanded).collect()
+    } else {
+      expanded.chain(std::iter::once(name.to_owned())).collect()
+    }
+  }
+
+  pub fn resolve_with_search<F, T>(&self, name: &str, mut lookup: F) -> Option<(String, T)>
+  where
+    F: FnMut(&str) -> Option<T>,
+  {
+    self
+      .search_candidates(name)
+      .into_iter()
+      .find_map(|candidate| lookup(&candidate).map(|result| (candidate, result)))
+  }
+}
+
+fn parse_option(conf: &mut ResolvConf, option: &str) {
+  let (name, value) = match option.find(':') {
+    Some(index) => (&option[..index], option[index + 1..].parse::<u32>().ok()),
+    None => (option, None),
+  };
+  match (name, value) {
+    ("ndots", Some(value)) => conf.ndots = value.min(15) as u8,
+    ("timeout", Some(value)) => conf.timeout = value.min(30),
+    ("attempts", Some(value)) => conf.attempts = value.min(5),
+    ("rotate", _) => conf.rotate = true,
+    ("edns0", _) => conf.edns0 = true,
+    _ => {}
+  }
+}
+
+pub fn parse_resolv_conf(text: &str) -> ResolvConf {
+  let mut conf = ResolvConf::default();
+
+  for line in text.lines() {
+    let line = line.split(['#', ';']).next().unwrap_or("");
+    let mut tokens = line.split_whitespace();
+    match tokens.next() {
+      Some("nameserver") => {
+        let address = tokens
+          .next()
+          .and_then(|a| a.split('%').next())
+          .and_then(|a| a.parse().ok());
+        if let Some(address) = address {
+          if conf.nameservers.len() < MAX_NAMESERVERS {
+            conf.nameservers.push(address);
+          }
+        }
+      }
+      Some("domain") => {
+        conf.domain = tokens.next().map(str::to_owned);
+        conf.search.clear();
+      }
+      Some("search") => {
+        conf.search = tokens.map(str::to_owned).collect();
+        conf.domain = None;
+      }
+      Some("options") => tokens.for_each(|o| parse_option(&mut conf, o)),
+      _ => {}
+    }
+  }
+
+  conf
+}
+
+mod test {
+
+  #[test]
+  fn parse_resolv_conf() {
+    let conf = super::parse_resolv_conf(
+      "# generated\n\
+       nameserver 192.168.1.1\n\
+       nameserver fe80::1%en0 ; router\n\
+       nameserver bogus\n\
+       domain corp.example\n\
+       search lan home.arpa\n\
+       options ndots:2 timeout:60 rotate\n",
+    );
+    assert_eq!(
+      vec![
+        "192.168.1.1".parse::<std::net::IpAddr>().unwrap(),
+        "fe80::1".parse().unwrap()
+      ],
+      conf.nameservers
+    );
+    assert_eq!(vec!["lan", "home.arpa"], conf.search_list());
+    assert_eq!(
+      (2, 30, 2, true),
+      (conf.ndots, conf.timeout, conf.attempts, conf.rotate)
+    );
+  }
+
+  #[test]
+  fn search_candidates() {
+    let conf = super::parse_resolv_conf(
+      "search lan home.arpa
+options ndots:1
+",
+    );
+    assert_eq!(
+      vec!["printer.lan", "printer.home.arpa", "printer"],
+      conf.search_candidates("printer")
+    );
+    assert_eq!(
+      vec!["printer.corp", "printer.corp.lan", "printer.corp.home.arpa"],
+      conf.search_candidates("printer.corp")
+    );
+    assert_eq!(vec!["printer.lan"], conf.search_candidates("printer.lan."));
+  }
+
+  #[test]
+  fn resolve_with_search() {
+    let conf = super::parse_resolv_conf(
+      "search lan home.arpa
+",
+    );
+    let result = conf.resolve_with_search("nas", |name| match name {
+      "nas.home.arpa" => Some("192.168.1.5"),
+      _ => None,
+    });
+    assert_eq!(Some(("nas.home.arpa".to_owned(), "192.168.1.5")), result);
+    assert_eq!(None, conf.resolve_with_search("nas", |_| None::<()>));
+  }
+
+  #[test]
+  fn parse_resolv_conf_with_domain() {
+    let conf = super::parse_resolv_conf("search lan\ndomain corp.example\n");
+    assert_eq!(vec!["corp.example"], conf.search_list());
+    assert_eq!(super::ResolvConf::default().ndots, conf.ndots);
+  }
+}
@@ -1,8 +1,18 @@
-use crate::shared::{extract_domain_name, parse_class, parse_name, Class, Label, ParseError};
+use crate::iana::{type_code, type_mnemonic};
+use crate::name::Name;
+#[allow(deprecated)]
+use crate::shared::Type;
+use crate::shared::{
+  encode_name, encoded_name_len, extract_domain_name, labels_to_name, name_to_labels,
+  parse_class_with_top_bit, parse_name, Class, ErrorCode, Label, Limits, ParseError, ParseOptions,
+  Section,
+};
+use std::cmp::Ordering;
 use std::fmt::Debug;
+use std::ops::Range;
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum ResourceRecordType {
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RecordType {
   A,
   AAAA,
   CNAME,
@@ -14,23 +24,107 @@ pub enum ResourceRecordType {
   OPT,
   SRV,
   NSEC,
+  MD,
+  MF,
+  MB,
+  MG,
+  MR,
+  NULL,
+  WKS,
+  HINFO,
+  MINFO,
   Other(u16),
 }
 
-#[derive(Debug)]
+#[deprecated(note = "Use RecordType instead")]
+pub type ResourceRecordType = RecordType;
+
+#[allow(deprecated)]
+impl From<Type> for RecordType {
+  fn from(record_type: Type) -> Self {
+    parse_resource_record_type(record_type.code().to_be_bytes())
+  }
+}
+
+impl RecordType {
+  pub fn code(&self) -> u16 {
+    match self {
+      RecordType::A => 1,
+      RecordType::NS => 2,
+      RecordType::CNAME => 5,
+      RecordType::SOA => 6,
+      RecordType::PTR => 12,
+      RecordType::MX => 15,
+      RecordType::TXT => 16,
+      RecordType::AAAA => 28,
+      RecordType::SRV => 33,
+      RecordType::OPT => 41,
+      RecordType::NSEC => 47,
+      RecordType::MD => 3,
+      RecordType::MF => 4,
+      RecordType::MB => 7,
+      RecordType::MG => 8,
+      RecordType::MR => 9,
+      RecordType::NULL => 10,
+      RecordType::WKS => 11,
+      RecordType::HINFO => 13,
+      RecordType::MINFO => 14,
+      RecordType::Other(n) => *n,
+    }
+  }
+}
+
+impl std::fmt::Display for RecordType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match type_mnemonic(self.code()) {
+      Some(mnemonic) => write!(f, "{}", mnemonic),
+      None => write!(f, "TYPE{}", self.code()),
+    }
+  }
+}
+
+impl std::str::FromStr for RecordType {
+  type Err = ParseError;
+
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    match type_code(name) {
+      Some(code) => Ok(parse_resource_record_type(code.to_be_bytes())),
+      None => Err(ParseError::ResourceRecordError(
+        ErrorCode::InvalidPresentation,
+        format!("Unknown resource record type: {}", name),
+      )),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SRV {
-  priority: u16,
-  weight: u16,
-  port: u16,
+  pub priority: u16,
+  pub weight: u16,
+  pub port: u16,
+  pub target: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SOA {
+  pub mname: String,
+  pub rname: String,
+  pub serial: u32,
+  pub refresh: u32,
+  pub retry: u32,
+  pub expire: u32,
+  pub minimum: u32,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ResourceRecordData {
   A(std::net::Ipv4Addr),
   AAAA(std::net::Ipv6Addr),
   SRV(SRV),
   PTR(String),
-  TXT(String),
+  NS(String),
+  SOA(SOA),
+  TXT(Vec<Vec<u8>>),
   Other(Vec<u8>),
 }
 
@@ -44,18 +138,82 @@ impl std::fmt::Display for ResourceRecordData {
   }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ResourceRecord {
   pub values: Vec<Label>,
   pub name: String,
-  pub resource_record_type: ResourceRecordType,
+  pub resource_record_type: RecordType,
   pub class: Class,
+  pub cache_flush: bool,
   pub ttl: u32,
   pub resource_record_data_length: u16,
+  pub resource_record_data_span: usize,
   pub resource_record_data: ResourceRecordData,
+  pub range: Range<usize>,
+}
+
+type ParsedResourceRecordData = (ResourceRecordData, usize);
+
+impl ResourceRecordData {
+  pub fn encoded_len(&self) -> usize {
+    match self {
+      ResourceRecordData::A(_) => 4,
+      ResourceRecordData::AAAA(_) => 16,
+      ResourceRecordData::SRV(srv) => 6 + encoded_name_len(&srv.target),
+      ResourceRecordData::PTR(name) | ResourceRecordData::NS(name) => encoded_name_len(name),
+      ResourceRecordData::SOA(soa) => {
+        encoded_name_len(&soa.mname) + encoded_name_len(&soa.rname) + 20
+      }
+      ResourceRecordData::TXT(strings) => strings.iter().fold(0, |sum, s| sum + s.len() + 1),
+      ResourceRecordData::Other(data) => data.len(),
+    }
+  }
+
+  pub fn canonical_bytes(&self) -> Vec<u8> {
+    self.uncompressed_bytes(true)
+  }
+
+  pub fn rdata_bytes(&self) -> Vec<u8> {
+    self.uncompressed_bytes(false)
+  }
+
+  pub fn cmp_rdata(&self, other: &ResourceRecordData) -> Ordering {
+    self.rdata_bytes().cmp(&other.rdata_bytes())
+  }
+
+  fn uncompressed_bytes(&self, fold_case: bool) -> Vec<u8> {
+    let canonical_name = |name: &str| Name::new(name).canonical_bytes(fold_case);
+    match self {
+      ResourceRecordData::A(address) => address.octets().to_vec(),
+      ResourceRecordData::AAAA(address) => address.octets().to_vec(),
+      ResourceRecordData::SRV(srv) => {
+        let mut bytes = vec![];
+        bytes.extend(&srv.priority.to_be_bytes());
+        bytes.extend(&srv.weight.to_be_bytes());
+        bytes.extend(&srv.port.to_be_bytes());
+        bytes.extend(canonical_name(&srv.target));
+        bytes
+      }
+      ResourceRecordData::PTR(name) | ResourceRecordData::NS(name) => canonical_name(name),
+      ResourceRecordData::SOA(soa) => {
+        let mut bytes = canonical_name(&soa.mname);
+        bytes.extend(canonical_name(&soa.rname));
+        for value in &[soa.serial, soa.refresh, soa.retry, soa.expire, soa.minimum] {
+          bytes.extend(&value.to_be_bytes());
+        }
+        bytes
+      }
+      ResourceRecordData::TXT(strings) => strings.iter().fold(vec![], |mut bytes, s| {
+        bytes.push(s.len().min(255) as u8);
+        bytes.extend(&s[..s.len().min(255)]);
+        bytes
+      }),
+      ResourceRecordData::Other(bytes) => bytes.clone(),
+    }
+  }
 }
 
-impl<'a> ResourceRecord {
+impl ResourceRecord {
   pub fn size(&self) -> usize {
     let type_length = 2;
     let class_length = 2;
@@ -70,74 +228,152 @@ impl<'a> ResourceRecord {
       + data_length_length
       + name_size
   }
+
+  pub fn encoded_len(&self) -> usize {
+    encoded_name_len(&self.name) + 10 + self.resource_record_data.encoded_len()
+  }
+
+  pub fn cmp_probe(&self, other: &ResourceRecord) -> Ordering {
+    self
+      .class
+      .code()
+      .cmp(&other.class.code())
+      .then(
+        self
+          .resource_record_type
+          .code()
+          .cmp(&other.resource_record_type.code()),
+      )
+      .then_with(|| {
+        self
+          .resource_record_data
+          .cmp_rdata(&other.resource_record_data)
+      })
+  }
+
+  pub fn data_range(&self) -> Range<usize> {
+    self.range.end - self.resource_record_data_length as usize..self.range.end
+  }
 }
 
 fn parse_resource_record_data(
   label_store: &mut Vec<Label>,
   offset: usize,
-  resource_record_type: &ResourceRecordType,
+  resource_record_type: &RecordType,
   _class: &Class,
   resource_data_length: u16,
   data: &[u8],
-) -> Result<ResourceRecordData, ParseError> {
-  if data.len() < resource_data_length as usize {
+  limits: &Limits,
+) -> Result<ParsedResourceRecordData, ParseError> {
+  if data.len() < offset + resource_data_length as usize {
     return Err(ParseError::ResourceRecordError(
+      ErrorCode::TruncatedRecord,
       "Data would overflow parsing resource record data".to_owned(),
     ));
   }
+  let data = &data[..offset + resource_data_length as usize];
 
   match resource_record_type {
-    ResourceRecordType::A => parse_resource_record_data_ip_a(offset, resource_data_length, data),
-    ResourceRecordType::AAAA => {
-      parse_resource_record_data_ip_aaaa(offset, resource_data_length, data)
+    RecordType::A => parse_resource_record_data_ip_a(offset, resource_data_length, data),
+    RecordType::AAAA => parse_resource_record_data_ip_aaaa(offset, resource_data_length, data),
+    RecordType::SRV => {
+      parse_resource_record_data_srv(label_store, offset, resource_data_length, data, limits)
+    }
+    RecordType::TXT => parse_resource_record_data_txt(offset, resource_data_length, data),
+    RecordType::PTR => {
+      parse_resource_record_data_ptr(label_store, offset, resource_data_length, data, limits)
+    }
+    RecordType::NS => {
+      parse_resource_record_data_ns(label_store, offset, resource_data_length, data, limits)
     }
-    ResourceRecordType::SRV => parse_resource_record_data_srv(offset, resource_data_length, data),
-    ResourceRecordType::TXT => parse_resource_record_data_txt(offset, resource_data_length, data),
-    ResourceRecordType::PTR => {
-      parse_resource_record_data_ptr(label_store, offset, resource_data_length, data)
+    RecordType::SOA => {
+      parse_resource_record_data_soa(label_store, offset, resource_data_length, data, limits)
     }
     _ => parse_resource_record_data_other(offset, resource_data_length, data),
   }
 }
 
-fn to_ascii(data: &[u8]) -> String {
-  data.iter().map(|c| *c as char).collect::<String>()
-}
-
 fn parse_resource_record_data_srv(
+  label_store: &mut Vec<Label>,
   offset: usize,
   resource_record_length: u16,
   data: &[u8],
-) -> Result<ResourceRecordData, ParseError> {
-  println!(
-    "{:?}",
-    &data[offset..offset + (resource_record_length as usize)]
-  );
-  Ok(ResourceRecordData::SRV(SRV {
-    priority: u16::from_be_bytes([data[offset], data[offset + 1]]),
-    weight: u16::from_be_bytes([data[offset + 2], data[offset + 3]]),
-    port: u16::from_be_bytes([data[offset + 4], data[offset + 5]]),
-  }))
+  limits: &Limits,
+) -> Result<ParsedResourceRecordData, ParseError> {
+  if resource_record_length < 7 {
+    return Err(ParseError::ResourceRecordError(
+      ErrorCode::DataLengthMismatch,
+      format!(
+        "Resource record data length is {} but SRV needs at least 7 bytes",
+        resource_record_length
+      ),
+    ));
+  }
+
+  let values = parse_name(offset + 6, data, limits)?;
+  values.iter().for_each(|v| label_store.push(v.clone()));
+  let target = extract_domain_name(label_store, &values)?;
+  let target_size = values.iter().fold(0, |sum, l| sum + l.size());
+
+  Ok((
+    ResourceRecordData::SRV(SRV {
+      priority: u16::from_be_bytes([data[offset], data[offset + 1]]),
+      weight: u16::from_be_bytes([data[offset + 2], data[offset + 3]]),
+      port: u16::from_be_bytes([data[offset + 4], data[offset + 5]]),
+      target,
+    }),
+    6 + target_size,
+  ))
 }
 
 fn parse_resource_record_data_txt(
   offset: usize,
   resource_record_length: u16,
   data: &[u8],
-) -> Result<ResourceRecordData, ParseError> {
-  Ok(ResourceRecordData::TXT(to_ascii(
-    &data[offset..offset + (resource_record_length as usize)],
-  )))
+) -> Result<ParsedResourceRecordData, ParseError> {
+  let txt_data = &data[offset..offset + (resource_record_length as usize)];
+  let mut strings = vec![];
+  let mut index = 0;
+
+  while index < txt_data.len() {
+    let count = txt_data[index] as usize;
+    if index + count >= txt_data.len() {
+      return Err(ParseError::ResourceRecordError(
+        ErrorCode::TruncatedRecord,
+        "Wrong TXT string count. Count would overflow data".to_owned(),
+      ));
+    }
+    strings.push(txt_data[index + 1..index + 1 + count].to_vec());
+    index += count + 1;
+  }
+
+  Ok((ResourceRecordData::TXT(strings), index))
 }
 
 fn parse_resource_record_data_other(
   offset: usize,
   resource_data_length: u16,
   data: &[u8],
-) -> Result<ResourceRecordData, ParseError> {
-  Ok(ResourceRecordData::Other(Vec::from(
-    &data[offset..offset + (resource_data_length as usize)],
-  )))
+) -> Result<ParsedResourceRecordData, ParseError> {
+  Ok((
+    ResourceRecordData::Other(Vec::from(
+      &data[offset..offset + (resource_data_length as usize)],
+    )),
+    resource_data_length as usize,
+  ))
+}
+
+fn parse_resource_record_data_name(
+  label_store: &mut Vec<Label>,
+  offset: usize,
+  data: &[u8],
+  limits: &Limits,
+) -> Result<(String, usize), ParseError> {
+  let values = parse_name(offset, data, limits)?;
+  values.iter().for_each(|v| label_store.push(v.clone()));
+  let name = extract_domain_name(label_store, &values)?;
+  let name_size = values.iter().fold(0, |sum, l| sum + l.size());
+  Ok((name, name_size))
 }
 
 fn parse_resource_record_data_ptr(
@@ -145,53 +381,114 @@ fn parse_resource_record_data_ptr(
   offset: usize,
   _resource_data_length: u16,
   data: &[u8],
-) -> Result<ResourceRecordData, ParseError> {
-  let values = parse_name(offset, data)?;
-  values.iter().for_each(|v| label_store.push(v.clone()));
-  let name = extract_domain_name(label_store, &values);
-  Ok(ResourceRecordData::PTR(name))
+  limits: &Limits,
+) -> Result<ParsedResourceRecordData, ParseError> {
+  let (name, name_size) = parse_resource_record_data_name(label_store, offset, data, limits)?;
+  Ok((ResourceRecordData::PTR(name), name_size))
 }
 
-fn parse_resource_record_data_ip_aaaa(
+fn parse_resource_record_data_ns(
+  label_store: &mut Vec<Label>,
   offset: usize,
   _resource_data_length: u16,
   data: &[u8],
-) -> Result<ResourceRecordData, ParseError> {
-  if data.len() < 16 {
+  limits: &Limits,
+) -> Result<ParsedResourceRecordData, ParseError> {
+  let (name, name_size) = parse_resource_record_data_name(label_store, offset, data, limits)?;
+  Ok((ResourceRecordData::NS(name), name_size))
+}
+
+fn parse_resource_record_data_soa(
+  label_store: &mut Vec<Label>,
+  offset: usize,
+  _resource_data_length: u16,
+  data: &[u8],
+  limits: &Limits,
+) -> Result<ParsedResourceRecordData, ParseError> {
+  let (mname, mname_size) = parse_resource_record_data_name(label_store, offset, data, limits)?;
+  let (rname, rname_size) =
+    parse_resource_record_data_name(label_store, offset + mname_size, data, limits)?;
+  let start = offset + mname_size + rname_size;
+
+  if data.len() < start + 20 {
+    return Err(ParseError::ResourceRecordError(
+      ErrorCode::TruncatedRecord,
+      "Data would overflow when parsing SOA resource".to_owned(),
+    ));
+  }
+  let field = |index: usize| {
+    let i = start + index * 4;
+    u32::from_be_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]])
+  };
+
+  Ok((
+    ResourceRecordData::SOA(SOA {
+      mname,
+      rname,
+      serial: field(0),
+      refresh: field(1),
+      retry: field(2),
+      expire: field(3),
+      minimum: field(4),
+    }),
+    mname_size + rname_size + 20,
+  ))
+}
+
+fn parse_resource_record_data_ip_aaaa(
+  offset: usize,
+  resource_data_length: u16,
+  data: &[u8],
+) -> Result<ParsedResourceRecordData, ParseError> {
+  if resource_data_length < 16 {
     return Err(ParseError::ResourceRecordError(
-      "Data would overflow when parsing IPv4 resource".to_owned(),
+      ErrorCode::DataLengthMismatch,
+      format!(
+        "Resource record data length is {} but an IPv6 address needs 16 bytes",
+        resource_data_length
+      ),
     ));
   }
 
-  Ok(ResourceRecordData::AAAA(std::net::Ipv6Addr::new(
-    u16::from_be_bytes([data[offset], data[offset + 1]]),
-    u16::from_be_bytes([data[offset + 2], data[offset + 3]]),
-    u16::from_be_bytes([data[offset + 4], data[offset + 5]]),
-    u16::from_be_bytes([data[offset + 6], data[offset + 7]]),
-    u16::from_be_bytes([data[offset + 8], data[offset + 9]]),
-    u16::from_be_bytes([data[offset + 10], data[offset + 11]]),
-    u16::from_be_bytes([data[offset + 12], data[offset + 13]]),
-    u16::from_be_bytes([data[offset + 14], data[offset + 15]]),
-  )))
+  Ok((
+    ResourceRecordData::AAAA(std::net::Ipv6Addr::new(
+      u16::from_be_bytes([data[offset], data[offset + 1]]),
+      u16::from_be_bytes([data[offset + 2], data[offset + 3]]),
+      u16::from_be_bytes([data[offset + 4], data[offset + 5]]),
+      u16::from_be_bytes([data[offset + 6], data[offset + 7]]),
+      u16::from_be_bytes([data[offset + 8], data[offset + 9]]),
+      u16::from_be_bytes([data[offset + 10], data[offset + 11]]),
+      u16::from_be_bytes([data[offset + 12], data[offset + 13]]),
+      u16::from_be_bytes([data[offset + 14], data[offset + 15]]),
+    )),
+    16,
+  ))
 }
 
 fn parse_resource_record_data_ip_a(
   offset: usize,
-  _resource_data_length: u16,
+  resource_data_length: u16,
   data: &[u8],
-) -> Result<ResourceRecordData, ParseError> {
-  if data.len() < 4 {
+) -> Result<ParsedResourceRecordData, ParseError> {
+  if resource_data_length < 4 {
     return Err(ParseError::ResourceRecordError(
-      "Data would overflow when parsing IPv4 resource".to_owned(),
+      ErrorCode::DataLengthMismatch,
+      format!(
+        "Resource record data length is {} but an IPv4 address needs 4 bytes",
+        resource_data_length
+      ),
     ));
   }
 
-  Ok(ResourceRecordData::A(std::net::Ipv4Addr::new(
-    data[offset],
-    data[offset + 1],
-    data[offset + 2],
-    data[offset + 3],
-  )))
+  Ok((
+    ResourceRecordData::A(std::net::Ipv4Addr::new(
+      data[offset],
+      data[offset + 1],
+      data[offset + 2],
+      data[offset + 3],
+    )),
+    4,
+  ))
 }
 
 fn parse_resource_data_length(data: [u8; 2]) -> u16 {
@@ -202,20 +499,29 @@ fn parse_ttl(data: [u8; 4]) -> u32 {
   u32::from_be_bytes(data)
 }
 
-fn parse_resource_record_type(data: [u8; 2]) -> ResourceRecordType {
+pub(crate) fn parse_resource_record_type(data: [u8; 2]) -> RecordType {
   match u16::from_be_bytes(data) {
-    1 => ResourceRecordType::A,
-    2 => ResourceRecordType::NS,
-    5 => ResourceRecordType::CNAME,
-    6 => ResourceRecordType::SOA,
-    12 => ResourceRecordType::PTR,
-    15 => ResourceRecordType::MX,
-    16 => ResourceRecordType::TXT,
-    28 => ResourceRecordType::AAAA,
-    33 => ResourceRecordType::SRV,
-    41 => ResourceRecordType::OPT,
-    47 => ResourceRecordType::NSEC,
-    n => ResourceRecordType::Other(n),
+    1 => RecordType::A,
+    2 => RecordType::NS,
+    3 => RecordType::MD,
+    4 => RecordType::MF,
+    5 => RecordType::CNAME,
+    6 => RecordType::SOA,
+    7 => RecordType::MB,
+    8 => RecordType::MG,
+    9 => RecordType::MR,
+    10 => RecordType::NULL,
+    11 => RecordType::WKS,
+    12 => RecordType::PTR,
+    13 => RecordType::HINFO,
+    14 => RecordType::MINFO,
+    15 => RecordType::MX,
+    16 => RecordType::TXT,
+    28 => RecordType::AAAA,
+    33 => RecordType::SRV,
+    41 => RecordType::OPT,
+    47 => RecordType::NSEC,
+    n => RecordType::Other(n),
   }
 }
 
@@ -223,17 +529,26 @@ fn parse_resource_record(
   label_store: &mut Vec<Label>,
   offset: usize,
   data: &[u8],
+  options: &ParseOptions,
 ) -> Result<ResourceRecord, ParseError> {
-  let values = parse_name(offset, data)?;
-  let name = extract_domain_name(label_store, &values);
-  let next_index = values.iter().fold(offset, |sum, l| sum + l.size());
+  let values = parse_name(offset, data, &options.limits)?;
   values.iter().for_each(|v| label_store.push(v.clone()));
+  let name = extract_domain_name(label_store, &values)?;
+  let next_index = values.iter().fold(offset, |sum, l| sum + l.size());
+
+  if data.len() < next_index + 10 {
+    return Err(ParseError::ResourceRecordError(
+      ErrorCode::TruncatedRecord,
+      "Data not long enough for resource record".to_owned(),
+    ));
+  }
 
   let resource_record_type_data: [u8; 2] = [data[next_index], data[next_index + 1]];
   let resource_record_type = parse_resource_record_type(resource_record_type_data);
 
   let resource_record_class_data: [u8; 2] = [data[next_index + 2], data[next_index + 3]];
-  let resource_record_class = parse_class(resource_record_class_data);
+  let (resource_record_class, cache_flush) =
+    parse_class_with_top_bit(resource_record_class_data, options);
 
   let ttl_data: [u8; 4] = [
     data[next_index + 4],
@@ -246,23 +561,364 @@ fn parse_resource_record(
   let resource_record_data_length_data: [u8; 2] = [data[next_index + 8], data[next_index + 9]];
   let resource_record_data_length = parse_resource_data_length(resource_record_data_length_data);
 
-  let resource_record_data = parse_resource_record_data(
+  let (resource_record_data, resource_record_data_span) = parse_resource_record_data(
     label_store,
     next_index + 10,
     &resource_record_type,
     &resource_record_class,
     resource_record_data_length,
     data,
+    &options.limits,
   )?;
 
+  if resource_record_data_span != resource_record_data_length as usize
+    && !options.allow_data_length_mismatch
+  {
+    return Err(ParseError::ResourceRecordError(
+      ErrorCode::DataLengthMismatch,
+      format!(
+        "Resource record data length is {} but {} bytes were parsed",
+        resource_record_data_length, resource_record_data_span
+      ),
+    ));
+  }
+
   Ok(ResourceRecord {
     values,
     name,
     resource_record_type,
     class: resource_record_class,
+    cache_flush,
     ttl,
     resource_record_data_length,
+    resource_record_data_span,
     resource_record_data,
+    range: offset..next_index + 10 + resource_record_data_length as usize,
+  })
+}
+
+fn split_presentation_tokens(line: &str) -> Result<Vec<String>, ParseError> {
+  let mut tokens = vec![];
+  let mut token = String::new();
+  let mut quoted = false;
+  let mut chars = line.chars();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => {
+        token.push(c);
+        match chars.next() {
+          Some(escaped) => token.push(escaped),
+          None => {
+            return Err(ParseError::ResourceRecordError(
+              ErrorCode::InvalidPresentation,
+              "Line ends with an escape character".to_owned(),
+            ))
+          }
+        }
+      }
+      '"' => {
+        token.push(c);
+        quoted = !quoted;
+      }
+      ';' if !quoted => break,
+      c if c.is_whitespace() && !quoted => {
+        if !token.is_empty() {
+          tokens.push(std::mem::take(&mut token));
+        }
+      }
+      c => token.push(c),
+    }
+  }
+
+  if quoted {
+    return Err(ParseError::ResourceRecordError(
+      ErrorCode::InvalidPresentation,
+      "Unterminated quoted string".to_owned(),
+    ));
+  }
+
+  if !token.is_empty() {
+    tokens.push(token);
+  }
+
+  Ok(tokens)
+}
+
+fn parse_character_string(token: &str) -> Result<Vec<u8>, ParseError> {
+  let unquoted = match token.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+    Some(unquoted) => unquoted,
+    None => token,
+  };
+
+  let bytes = unquoted.as_bytes();
+  let mut data = vec![];
+  let mut index = 0;
+
+  while index < bytes.len() {
+    if bytes[index] != b'\\' {
+      data.push(bytes[index]);
+      index += 1;
+      continue;
+    }
+
+    let escape = &bytes[index + 1..];
+    match escape.first() {
+      Some(d) if d.is_ascii_digit() => {
+        let value = escape
+          .get(..3)
+          .filter(|digits| digits.iter().all(|d| d.is_ascii_digit()))
+          .map(|digits| digits.iter().fold(0u16, |v, d| v * 10 + (d - b'0') as u16));
+        match value {
+          Some(value) if value <= 255 => data.push(value as u8),
+          _ => {
+            return Err(ParseError::ResourceRecordError(
+              ErrorCode::InvalidPresentation,
+              format!("Invalid decimal escape in character string: {}", token),
+            ))
+          }
+        }
+        index += 4;
+      }
+      Some(escaped) => {
+        data.push(*escaped);
+        index += 2;
+      }
+      None => {
+        return Err(ParseError::ResourceRecordError(
+          ErrorCode::InvalidPresentation,
+          format!("Character string ends with an escape character: {}", token),
+        ))
+      }
+    }
+  }
+
+  if data.len() > 255 {
+    return Err(ParseError::ResourceRecordError(
+      ErrorCode::InvalidPresentation,
+      format!("Character string exceeds limit of 255: {}", token),
+    ));
+  }
+
+  Ok(data)
+}
+
+fn parse_generic_rdata(tokens: &[String]) -> Result<Vec<u8>, ParseError> {
+  let length = tokens.get(1).and_then(|t| t.parse::<usize>().ok());
+  let hex = tokens[2.min(tokens.len())..].concat();
+  let data = (0..hex.len())
+    .step_by(2)
+    .map(|i| {
+      hex
+        .get(i..i + 2)
+        .and_then(|b| u8::from_str_radix(b, 16).ok())
+    })
+    .collect::<Option<Vec<u8>>>();
+
+  match (length, data) {
+    (Some(length), Some(data)) if data.len() == length => Ok(data),
+    _ => Err(ParseError::ResourceRecordError(
+      ErrorCode::InvalidPresentation,
+      format!("Invalid generic rdata: {}", tokens.join(" ")),
+    )),
+  }
+}
+
+fn parse_rdata_token<T: std::str::FromStr>(
+  tokens: &[String],
+  index: usize,
+) -> Result<T, ParseError> {
+  tokens
+    .get(index)
+    .and_then(|t| t.parse::<T>().ok())
+    .ok_or_else(|| {
+      ParseError::ResourceRecordError(
+        ErrorCode::InvalidPresentation,
+        format!("Invalid rdata: {}", tokens.join(" ")),
+      )
+    })
+}
+
+fn encode_rdata(
+  resource_record_type: &RecordType,
+  tokens: &[String],
+) -> Result<Vec<u8>, ParseError> {
+  if tokens.first().map(|t| t.as_str()) == Some("\\#") {
+    return parse_generic_rdata(tokens);
+  }
+
+  let rdata = match resource_record_type {
+    RecordType::A => parse_rdata_token::<std::net::Ipv4Addr>(tokens, 0)?
+      .octets()
+      .to_vec(),
+    RecordType::AAAA => parse_rdata_token::<std::net::Ipv6Addr>(tokens, 0)?
+      .octets()
+      .to_vec(),
+    RecordType::PTR | RecordType::NS | RecordType::CNAME => {
+      encode_name(&parse_rdata_token::<String>(tokens, 0)?)?
+    }
+    RecordType::MX => {
+      let mut rdata = parse_rdata_token::<u16>(tokens, 0)?.to_be_bytes().to_vec();
+      rdata.extend(encode_name(&parse_rdata_token::<String>(tokens, 1)?)?);
+      rdata
+    }
+    RecordType::SRV => {
+      let mut rdata = vec![];
+      for index in 0..3 {
+        rdata.extend(&parse_rdata_token::<u16>(tokens, index)?.to_be_bytes());
+      }
+      rdata.extend(encode_name(&parse_rdata_token::<String>(tokens, 3)?)?);
+      rdata
+    }
+    RecordType::SOA => {
+      let mut rdata = encode_name(&parse_rdata_token::<String>(tokens, 0)?)?;
+      rdata.extend(encode_name(&parse_rdata_token::<String>(tokens, 1)?)?);
+      for index in 2..7 {
+        rdata.extend(&parse_rdata_token::<u32>(tokens, index)?.to_be_bytes());
+      }
+      rdata
+    }
+    RecordType::TXT if tokens.is_empty() => {
+      return Err(ParseError::ResourceRecordError(
+        ErrorCode::InvalidPresentation,
+        "TXT record needs at least one character string".to_owned(),
+      ))
+    }
+    RecordType::TXT => {
+      let mut rdata = vec![];
+      for token in tokens {
+        let string = parse_character_string(token)?;
+        rdata.push(string.len() as u8);
+        rdata.extend(string);
+      }
+      rdata
+    }
+    _ => {
+      return Err(ParseError::ResourceRecordError(
+        ErrorCode::InvalidPresentation,
+        format!(
+          "Unsupported presentation format for {:?}, use \\# instead",
+          resource_record_type
+        ),
+      ))
+    }
+  };
+
+  Ok(rdata)
+}
+
+pub fn parse_resource_record_data_str(
+  resource_record_type: &RecordType,
+  text: &str,
+) -> Result<ResourceRecordData, ParseError> {
+  let rdata = encode_rdata(resource_record_type, &split_presentation_tokens(text)?)?;
+  if rdata.len() > u16::MAX as usize {
+    return Err(ParseError::ResourceRecordError(
+      ErrorCode::InvalidPresentation,
+      "Resource record data exceeds limit of 65535".to_owned(),
+    ));
+  }
+  let (resource_record_data, _) = parse_resource_record_data(
+    &mut vec![],
+    0,
+    resource_record_type,
+    &Class::IN,
+    rdata.len() as u16,
+    &rdata,
+    &Limits::default(),
+  )?;
+  Ok(resource_record_data)
+}
+
+impl std::str::FromStr for SRV {
+  type Err = ParseError;
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    match parse_resource_record_data_str(&RecordType::SRV, text)? {
+      ResourceRecordData::SRV(srv) => Ok(srv),
+      _ => unreachable!(),
+    }
+  }
+}
+
+impl std::str::FromStr for SOA {
+  type Err = ParseError;
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    match parse_resource_record_data_str(&RecordType::SOA, text)? {
+      ResourceRecordData::SOA(soa) => Ok(soa),
+      _ => unreachable!(),
+    }
+  }
+}
+
+pub fn parse_resource_record_line(line: &str) -> Result<ResourceRecord, ParseError> {
+  let tokens = split_presentation_tokens(line)?;
+  if tokens.len() < 2 {
+    return Err(ParseError::ResourceRecordError(
+      ErrorCode::InvalidPresentation,
+      format!("Resource record line is missing fields: {}", line),
+    ));
+  }
+
+  let values = name_to_labels(0, &tokens[0])?;
+  let mut ttl = None;
+  let mut class = None;
+  let mut index = 1;
+
+  while index < tokens.len() {
+    match (
+      tokens[index].parse::<u32>(),
+      tokens[index].parse::<Class>().ok(),
+    ) {
+      (Ok(value), _) if ttl.is_none() => ttl = Some(value),
+      (_, Some(value)) if class.is_none() => class = Some(value),
+      _ => break,
+    }
+    index += 1;
+  }
+
+  let resource_record_type = match tokens.get(index) {
+    Some(name) => name.parse::<RecordType>()?,
+    None => {
+      return Err(ParseError::ResourceRecordError(
+        ErrorCode::InvalidPresentation,
+        format!("Resource record line is missing a type: {}", line),
+      ))
+    }
+  };
+  let class = class.unwrap_or(Class::IN);
+
+  let rdata = encode_rdata(&resource_record_type, &tokens[index + 1..])?;
+  if rdata.len() > u16::MAX as usize {
+    return Err(ParseError::ResourceRecordError(
+      ErrorCode::InvalidPresentation,
+      "Resource record data exceeds limit of 65535".to_owned(),
+    ));
+  }
+  let resource_record_data_length = rdata.len() as u16;
+  let (resource_record_data, resource_record_data_span) = parse_resource_record_data(
+    &mut vec![],
+    0,
+    &resource_record_type,
+    &class,
+    resource_record_data_length,
+    &rdata,
+    &Limits::default(),
+  )?;
+  let size = values.iter().fold(10, |sum, l| sum + l.size()) + rdata.len();
+
+  Ok(ResourceRecord {
+    name: labels_to_name(&values),
+    values,
+    resource_record_type,
+    class,
+    cache_flush: false,
+    ttl: ttl.unwrap_or(0),
+    resource_record_data_length,
+    resource_record_data_span,
+    resource_record_data,
+    range: 0..size,
   })
 }
 
@@ -271,11 +927,14 @@ pub fn parse_resource_records(
   start_offset: usize,
   count: u16,
   data: &[u8],
+  options: &ParseOptions,
+  section: Section,
 ) -> Result<Vec<ResourceRecord>, ParseError> {
   let mut answers = vec![];
   let mut current_offset = start_offset;
-  for _ in 0..count {
-    let answer = parse_resource_record(label_store, current_offset, data)?;
+  for index in 0..count as usize {
+    let answer = parse_resource_record(label_store, current_offset, data, options)
+      .map_err(|e| ParseError::SectionError(section, index, Box::new(e)))?;
     current_offset += answer.size();
     answers.push(answer);
   }
@@ -284,11 +943,25 @@ pub fn parse_resource_records(
 
 mod test {
 
+  #[test]
+  #[allow(deprecated)]
+  fn record_type_from_type() {
+    assert_eq!(
+      super::RecordType::MINFO,
+      super::RecordType::from(crate::shared::Type::MINFO)
+    );
+    assert_eq!(
+      super::RecordType::Other(0),
+      super::RecordType::from(crate::shared::Type::Invalid)
+    );
+  }
+
   #[test]
   fn parse_resource_record_type() {
     let data = &[
-      (super::ResourceRecordType::A, [0, 1]),
-      (super::ResourceRecordType::Other(257), [1, 1]),
+      (super::RecordType::A, [0, 1]),
+      (super::RecordType::HINFO, [0, 13]),
+      (super::RecordType::Other(257), [1, 1]),
     ];
     for td in data {
       let result = super::parse_resource_record_type(td.1);
@@ -296,6 +969,96 @@ mod test {
     }
   }
 
+  #[test]
+  fn parse_resource_record_data_txt() {
+    let data = [4, 97, 61, 98, 99, 0, 2, 100, 101];
+    match super::parse_resource_record_data_txt(0, 9, &data) {
+      Ok((super::ResourceRecordData::TXT(strings), 9)) => {
+        assert_eq!(vec![b"a=bc".to_vec(), vec![], b"de".to_vec()], strings);
+      }
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_resource_record_data_txt_with_binary_string() {
+    let data = [3, 0xff, 0xfe, 0, 1, 97];
+    let (result, _) = super::parse_resource_record_data_txt(0, 6, &data).unwrap();
+    assert_eq!(
+      super::ResourceRecordData::TXT(vec![vec![0xff, 0xfe, 0], b"a".to_vec()]),
+      result
+    );
+    assert_eq!(data.to_vec(), result.rdata_bytes());
+    assert_eq!(data.len(), result.encoded_len());
+  }
+
+  #[test]
+  fn parse_resource_record_data_txt_with_overflowing_count() {
+    let data = [4, 97, 61, 98];
+    match super::parse_resource_record_data_txt(0, 4, &data) {
+      Err(super::ParseError::ResourceRecordError(..)) => {}
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn resource_record_type_code() {
+    for code in &[1u16, 2, 5, 6, 12, 15, 16, 28, 33, 41, 47, 99] {
+      let resource_record_type = super::parse_resource_record_type(code.to_be_bytes());
+      assert_eq!(*code, resource_record_type.code());
+    }
+  }
+
+  #[test]
+  fn resource_record_type_display_and_from_str() {
+    let test_data = [
+      ("AAAA", super::RecordType::AAAA),
+      ("CAA", super::RecordType::Other(257)),
+      ("TYPE65280", super::RecordType::Other(65280)),
+    ];
+    for (name, resource_record_type) in &test_data {
+      assert_eq!(*name, resource_record_type.to_string());
+      assert_eq!(Ok(resource_record_type.clone()), name.parse());
+    }
+    assert!("BOGUS".parse::<super::RecordType>().is_err());
+  }
+
+  #[test]
+  fn parse_resource_record_data_str() {
+    assert_eq!(
+      super::ResourceRecordData::TXT(vec![b"md=Google Home".to_vec(), b"ve=05".to_vec()]),
+      super::parse_resource_record_data_str(&super::RecordType::TXT, "\"md=Google Home\" ve=05")
+        .unwrap()
+    );
+    assert_eq!(
+      super::ResourceRecordData::A("192.168.1.10".parse().unwrap()),
+      super::parse_resource_record_data_str(&super::RecordType::A, "192.168.1.10").unwrap()
+    );
+    let srv = "10 5 8009 device.local".parse::<super::SRV>().unwrap();
+    assert_eq!(
+      (10, 5, 8009, "device.local"),
+      (srv.priority, srv.weight, srv.port, srv.target.as_str())
+    );
+    match "10 5 device.local".parse::<super::SRV>() {
+      Err(super::ParseError::ResourceRecordError(..)) => {}
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn cmp_rdata() {
+    let data = |address: &str| super::ResourceRecordData::A(address.parse().unwrap());
+    assert_eq!(
+      std::cmp::Ordering::Less,
+      data("169.254.99.200").cmp_rdata(&data("169.254.200.50"))
+    );
+    assert_eq!(
+      std::cmp::Ordering::Greater,
+      super::ResourceRecordData::PTR("a.local".to_owned())
+        .cmp_rdata(&super::ResourceRecordData::PTR("A.local".to_owned()))
+    );
+  }
+
   #[test]
   fn parse_ttl() {
     let data = [1, 1, 1, 1];
@@ -311,4 +1074,184 @@ mod test {
       assert_eq!(td.0, result);
     }
   }
+
+  #[test]
+  fn parse_resource_record_line_a() {
+    let result = super::parse_resource_record_line("Living-Room.local.\t120\tIN\tA\t192.168.1.137");
+    let record = result.unwrap();
+    assert_eq!("Living-Room.local", record.name);
+    assert_eq!(super::RecordType::A, record.resource_record_type);
+    assert_eq!(super::Class::IN, record.class);
+    assert_eq!(120, record.ttl);
+    assert_eq!(4, record.resource_record_data_length);
+    match record.resource_record_data {
+      super::ResourceRecordData::A(ip) => assert_eq!(std::net::Ipv4Addr::new(192, 168, 1, 137), ip),
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_resource_record_line_srv() {
+    let result = super::parse_resource_record_line(
+      "Living\\ Room._googlecast._tcp.local. 120 IN SRV 0 0 8009 e0719ee5.local. ; comment",
+    );
+    let record = result.unwrap();
+    assert_eq!("Living\\032Room._googlecast._tcp.local", record.name);
+    assert_eq!(22, record.resource_record_data_length);
+    match record.resource_record_data {
+      super::ResourceRecordData::SRV(srv) => {
+        assert_eq!(
+          (0, 0, 8009, "e0719ee5.local".to_owned()),
+          (srv.priority, srv.weight, srv.port, srv.target)
+        );
+      }
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_resource_record_line_soa() {
+    let record = super::parse_resource_record_line(
+      "example.com. 3600 IN SOA ns.icann.org. noc.dns.icann.org. 2024010101 7200 3600 1209600 3600",
+    )
+    .unwrap();
+    assert_eq!(
+      super::ResourceRecordData::SOA(super::SOA {
+        mname: "ns.icann.org".to_owned(),
+        rname: "noc.dns.icann.org".to_owned(),
+        serial: 2024010101,
+        refresh: 7200,
+        retry: 3600,
+        expire: 1209600,
+        minimum: 3600,
+      }),
+      record.resource_record_data
+    );
+  }
+
+  #[test]
+  fn parse_resource_record_line_txt() {
+    let result =
+      super::parse_resource_record_line("a.local 4500 TXT \"fn=Living Room\" \"a\\\"b\" c\\059d");
+    let record = result.unwrap();
+    assert_eq!(4500, record.ttl);
+    match record.resource_record_data {
+      super::ResourceRecordData::TXT(strings) => {
+        assert_eq!(
+          vec![
+            b"fn=Living Room".to_vec(),
+            b"a\"b".to_vec(),
+            b"c;d".to_vec()
+          ],
+          strings
+        );
+      }
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_resource_record_line_txt_with_empty_string() {
+    let record = super::parse_resource_record_line("a.local 4500 TXT \"\"").unwrap();
+    assert_eq!(1, record.resource_record_data_length);
+    assert_eq!(vec![0], record.resource_record_data.rdata_bytes());
+  }
+
+  #[test]
+  fn parse_resource_record_line_generic() {
+    let result = super::parse_resource_record_line("a.local. 120 IN TYPE1 \\# 4 c0a8 0101");
+    let record = result.unwrap();
+    match record.resource_record_data {
+      super::ResourceRecordData::A(ip) => assert_eq!(std::net::Ipv4Addr::new(192, 168, 1, 1), ip),
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_resource_record_line_and_fail() {
+    for line in &[
+      "a.local.",
+      "a.local. 120 IN",
+      "a.local. 120 IN A 192.168.1",
+      "a.local. 120 IN NSEC b.local. A",
+      "a.local. 120 IN TXT \"abc",
+      "a.local. 120 IN TXT",
+      "a.local. 120 IN A \\# 4 c0a801",
+    ] {
+      match super::parse_resource_record_line(line) {
+        Err(super::ParseError::ResourceRecordError(..)) => {}
+        _ => panic!(),
+      }
+    }
+  }
+
+  #[test]
+  fn parse_resource_record_with_mismatching_data_length() {
+    let data = [
+      1, 97, 0, 0, 12, 0, 1, 0, 0, 0, 120, 0, 6, 1, 98, 0, 1, 99, 0,
+    ];
+    match super::parse_resource_record(&mut vec![], 0, &data, &Default::default()) {
+      Err(super::ParseError::ResourceRecordError(..)) => {}
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_resource_record_data_span() {
+    let data = [1, 97, 0, 0, 12, 0, 1, 0, 0, 0, 120, 0, 2, 192, 0];
+    let record = super::parse_resource_record(&mut vec![], 0, &data, &Default::default()).unwrap();
+    assert_eq!(2, record.resource_record_data_length);
+    assert_eq!(2, record.resource_record_data_span);
+    assert_eq!(15, record.size());
+  }
+
+  #[test]
+  fn parse_resource_record_with_truncated_data() {
+    let data = [1, 97, 0, 0, 12, 0, 1, 0, 0];
+    match super::parse_resource_record(&mut vec![], 0, &data, &Default::default()) {
+      Err(super::ParseError::ResourceRecordError(..)) => {}
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_resource_record_with_permissive_profile() {
+    let options = crate::shared::ParseOptions::from(crate::shared::Profile::PermissiveMdns);
+    let data = [
+      1, 97, 0, 0, 12, 128, 1, 0, 0, 0, 120, 0, 6, 1, 98, 0, 1, 99, 0,
+    ];
+    let record = super::parse_resource_record(&mut vec![], 0, &data, &options).unwrap();
+    assert_eq!(super::Class::IN, record.class);
+    assert!(record.cache_flush);
+    assert_eq!(6, record.resource_record_data_length);
+    assert_eq!(3, record.resource_record_data_span);
+    assert_eq!(19, record.size());
+  }
+
+  #[test]
+  fn parse_resource_record_with_strict_profile() {
+    let options = crate::shared::ParseOptions::from(crate::shared::Profile::Strict);
+    let data = [1, 97, 0, 0, 1, 128, 1, 0, 0, 0, 120, 0, 4, 192, 168, 1, 2];
+    let record = super::parse_resource_record(&mut vec![], 0, &data, &options).unwrap();
+    assert_eq!(super::Class::Other(0x8001), record.class);
+    assert!(!record.cache_flush);
+  }
+
+  #[test]
+  fn parse_resource_record_with_short_address() {
+    let options = crate::shared::ParseOptions::from(crate::shared::Profile::PermissiveMdns);
+    let data = [1, 97, 0, 0, 1, 0, 1, 0, 0, 0, 120, 0, 2, 192, 168, 1, 2];
+    let error = super::parse_resource_record(&mut vec![], 0, &data, &options).unwrap_err();
+    assert_eq!(
+      crate::shared::ErrorCode::DataLengthMismatch,
+      error.classify()
+    );
+  }
+
+  #[test]
+  fn parse_resource_record_with_name_beyond_data_length() {
+    let options = crate::shared::ParseOptions::from(crate::shared::Profile::PermissiveMdns);
+    let data = [1, 97, 0, 0, 12, 0, 1, 0, 0, 0, 120, 0, 2, 1, 98, 0];
+    assert!(super::parse_resource_record(&mut vec![], 0, &data, &options).is_err());
+  }
 }
@@ -0,0 +1,119 @@
+use crate::shared::{escape_label, split_label_bytes, ErrorCode, ParseError};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ServiceInstanceName {
+  pub instance: String,
+  pub service_type: String,
+  pub domain: String,
+}
+
+fn is_service_protocol(label: &[u8]) -> bool {
+  label.eq_ignore_ascii_case(b"_tcp") || label.eq_ignore_ascii_case(b"_udp")
+}
+
+pub fn parse_service_instance_name(name: &str) -> Result<ServiceInstanceName, ParseError> {
+  let labels = split_label_bytes(name)?;
+
+  if labels.len() < 4 {
+    return Err(ParseError::NameError(
+      ErrorCode::InvalidName,
+      format!("Service instance name needs at least four labels: {}", name),
+    ));
+  }
+
+  if !labels[1].starts_with(b"_") || !is_service_protocol(&labels[2]) {
+    return Err(ParseError::NameError(
+      ErrorCode::InvalidName,
+      format!("Service instance name is missing a service type: {}", name),
+    ));
+  }
+
+  let instance = String::from_utf8(labels[0].clone()).map_err(|_| {
+    ParseError::NameError(
+      ErrorCode::InvalidName,
+      format!("Service instance name is not valid UTF-8: {}", name),
+    )
+  })?;
+
+  Ok(ServiceInstanceName {
+    instance,
+    service_type: format!("{}.{}", escape_label(&labels[1]), escape_label(&labels[2])),
+    domain: labels[3..]
+      .iter()
+      .map(|l| escape_label(l))
+      .collect::<Vec<String>>()
+      .join("."),
+  })
+}
+
+pub fn format_service_instance_name(name: &ServiceInstanceName) -> String {
+  format!(
+    "{}.{}.{}",
+    escape_label(name.instance.as_bytes()),
+    name.service_type,
+    name.domain
+  )
+}
+
+mod test {
+
+  #[test]
+  fn parse_service_instance_name() {
+    let result = super::parse_service_instance_name("Living Room._googlecast._tcp.local");
+    assert_eq!(
+      Ok(super::ServiceInstanceName {
+        instance: "Living Room".to_owned(),
+        service_type: "_googlecast._tcp".to_owned(),
+        domain: "local".to_owned(),
+      }),
+      result
+    );
+  }
+
+  #[test]
+  fn parse_service_instance_name_with_escapes() {
+    let result = super::parse_service_instance_name("Dr\\. Who\\\\s TV._airplay._tcp.local.");
+    assert_eq!(
+      Ok(super::ServiceInstanceName {
+        instance: "Dr. Who\\s TV".to_owned(),
+        service_type: "_airplay._tcp".to_owned(),
+        domain: "local".to_owned(),
+      }),
+      result
+    );
+  }
+
+  #[test]
+  fn parse_service_instance_name_and_fail() {
+    for name in &[
+      "_hap._tcp.local",
+      "abc.def.ghi.local",
+      "abc._hap._sctp.local",
+    ] {
+      match super::parse_service_instance_name(name) {
+        Err(super::ParseError::NameError(..)) => {}
+        _ => panic!(),
+      }
+    }
+  }
+
+  #[test]
+  fn parse_service_instance_name_with_binary_instance() {
+    match super::parse_service_instance_name("\\255._hap._tcp.local") {
+      Err(super::ParseError::NameError(..)) => {}
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn format_service_instance_name() {
+    let name = super::ServiceInstanceName {
+      instance: "Printer v1.2".to_owned(),
+      service_type: "_ipp._tcp".to_owned(),
+      domain: "local".to_owned(),
+    };
+    let formatted = super::format_service_instance_name(&name);
+    assert_eq!("Printer\\032v1\\.2._ipp._tcp.local", formatted);
+    assert_eq!(Ok(name), super::parse_service_instance_name(&formatted));
+  }
+}
@@ -1,9 +1,165 @@
+use crate::iana::{class_code, class_mnemonic};
 #[derive(Debug, PartialEq, Eq)]
 pub enum ParseError {
-  HeaderError(String),
-  QueryLabelError(String),
-  QueryError(String),
-  ResourceRecordError(String),
+  HeaderError(ErrorCode, String),
+  QueryLabelError(ErrorCode, String),
+  QueryError(ErrorCode, String),
+  ResourceRecordError(ErrorCode, String),
+  NameError(ErrorCode, String),
+  TxtError(ErrorCode, String),
+  HexError(ErrorCode, String),
+  MessageError(ErrorCode, String),
+  LimitError(ErrorCode, String),
+  ConfigError(ErrorCode, String),
+  SectionError(Section, usize, Box<ParseError>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Section {
+  Question,
+  Answer,
+  Authority,
+  Additional,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+  TruncatedHeader,
+  TruncatedQuery,
+  TruncatedName,
+  TruncatedRecord,
+  PointerLoop,
+  LabelTooLong,
+  InvalidLabelType,
+  DataLengthMismatch,
+  TrailingData,
+  InvalidName,
+  InvalidPresentation,
+  InvalidTxt,
+  InvalidHex,
+  InvalidSection,
+  InvalidConfig,
+  LimitExceeded,
+}
+
+impl ErrorCode {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ErrorCode::TruncatedHeader => "E_TRUNCATED_HEADER",
+      ErrorCode::TruncatedQuery => "E_TRUNCATED_QUERY",
+      ErrorCode::TruncatedName => "E_TRUNCATED_NAME",
+      ErrorCode::TruncatedRecord => "E_TRUNCATED_RECORD",
+      ErrorCode::PointerLoop => "E_POINTER_LOOP",
+      ErrorCode::LabelTooLong => "E_LABEL_TOO_LONG",
+      ErrorCode::InvalidLabelType => "E_INVALID_LABEL_TYPE",
+      ErrorCode::DataLengthMismatch => "E_DATA_LENGTH_MISMATCH",
+      ErrorCode::TrailingData => "E_TRAILING_DATA",
+      ErrorCode::InvalidName => "E_INVALID_NAME",
+      ErrorCode::InvalidPresentation => "E_INVALID_PRESENTATION",
+      ErrorCode::InvalidTxt => "E_INVALID_TXT",
+      ErrorCode::InvalidHex => "E_INVALID_HEX",
+      ErrorCode::InvalidSection => "E_INVALID_SECTION",
+      ErrorCode::InvalidConfig => "E_INVALID_CONFIG",
+      ErrorCode::LimitExceeded => "E_LIMIT_EXCEEDED",
+    }
+  }
+}
+
+impl ParseError {
+  pub fn classify(&self) -> ErrorCode {
+    match self {
+      ParseError::HeaderError(code, _)
+      | ParseError::QueryLabelError(code, _)
+      | ParseError::QueryError(code, _)
+      | ParseError::ResourceRecordError(code, _)
+      | ParseError::NameError(code, _)
+      | ParseError::TxtError(code, _)
+      | ParseError::HexError(code, _)
+      | ParseError::MessageError(code, _)
+      | ParseError::LimitError(code, _)
+      | ParseError::ConfigError(code, _) => *code,
+      ParseError::SectionError(_, _, error) => error.classify(),
+    }
+  }
+
+  pub fn section(&self) -> Option<(Section, usize)> {
+    match self {
+      ParseError::SectionError(section, index, _) => Some((*section, *index)),
+      _ => None,
+    }
+  }
+
+  pub fn root_cause(&self) -> &ParseError {
+    match self {
+      ParseError::SectionError(_, _, error) => error.root_cause(),
+      error => error,
+    }
+  }
+
+  pub fn code(&self) -> &'static str {
+    self.classify().as_str()
+  }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Profile {
+  Strict,
+  PermissiveMdns,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+  pub max_labels: usize,
+  pub max_name_length: usize,
+  pub max_records: u16,
+  pub max_message_size: usize,
+}
+
+impl Default for Limits {
+  fn default() -> Self {
+    Limits {
+      max_labels: 127,
+      max_name_length: 255,
+      max_records: 4096,
+      max_message_size: 65535,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+  pub allow_class_top_bit: bool,
+  pub allow_trailing_data: bool,
+  pub allow_data_length_mismatch: bool,
+  pub retain_raw: bool,
+  pub limits: Limits,
+}
+
+impl From<Profile> for ParseOptions {
+  fn from(profile: Profile) -> Self {
+    match profile {
+      Profile::Strict => ParseOptions {
+        allow_class_top_bit: false,
+        allow_trailing_data: false,
+        allow_data_length_mismatch: false,
+        retain_raw: false,
+        limits: Limits::default(),
+      },
+      Profile::PermissiveMdns => ParseOptions {
+        allow_class_top_bit: true,
+        allow_trailing_data: true,
+        allow_data_length_mismatch: true,
+        retain_raw: false,
+        limits: Limits::default(),
+      },
+    }
+  }
+}
+
+impl Default for ParseOptions {
+  fn default() -> Self {
+    Profile::Strict.into()
+  }
 }
 
 const LABEL_TYPE_MASK: u8 = 0b11000000;
@@ -25,26 +181,26 @@ impl Label {
     }
   }
 
-  pub fn is_value_at_index(&self, index: u16) -> bool {
+  pub fn offset(&self) -> u16 {
     match self {
-      Label::Value(value_index, _) => *value_index != index,
-      _ => false,
+      Label::Value(offset, _) => *offset,
+      Label::Pointer(offset, _) => *offset,
     }
   }
 }
 
-fn resolve_pointer(all_labels: &Vec<Label>, pointer_value: u16) -> Vec<Label> {
+fn resolve_pointer(all_labels: &[Label], pointer_value: u16) -> Vec<Label> {
   let mut take_inclusive_found = false;
   all_labels
     .iter()
-    .skip_while(|l| l.is_value_at_index(pointer_value))
+    .skip_while(|l| l.offset() != pointer_value)
     .take_while(|l| {
       if take_inclusive_found {
         return false;
       }
 
       match l {
-        Label::Value(_, None) => {
+        Label::Value(_, None) | Label::Pointer(_, _) => {
           take_inclusive_found = true;
           true
         }
@@ -57,7 +213,18 @@ fn resolve_pointer(all_labels: &Vec<Label>, pointer_value: u16) -> Vec<Label> {
     })
 }
 
-pub fn extract_domain_name(label_store: &Vec<Label>, name_labels: &[Label]) -> String {
+pub fn extract_domain_name(
+  label_store: &[Label],
+  name_labels: &[Label],
+) -> Result<String, ParseError> {
+  extract_domain_name_visiting(label_store, name_labels, &mut vec![])
+}
+
+fn extract_domain_name_visiting(
+  label_store: &[Label],
+  name_labels: &[Label],
+  visited_pointers: &mut Vec<u16>,
+) -> Result<String, ParseError> {
   let mut found_pointer = false;
   name_labels
     .iter()
@@ -77,25 +244,159 @@ pub fn extract_domain_name(label_store: &Vec<Label>, name_labels: &[Label]) -> S
     })
     .map(|l| match l {
       Label::Pointer(_, pointer) => {
+        if visited_pointers.contains(pointer) {
+          return Err(ParseError::QueryLabelError(
+            ErrorCode::PointerLoop,
+            format!("Pointer loop detected at offset: {}", pointer),
+          ));
+        }
+        visited_pointers.push(*pointer);
         let pointer_name_labels = resolve_pointer(label_store, *pointer);
-        extract_domain_name(label_store, &pointer_name_labels)
+        extract_domain_name_visiting(label_store, &pointer_name_labels, visited_pointers)
       }
-      Label::Value(_, Some(data)) => std::str::from_utf8(data).unwrap().to_owned(),
-      Label::Value(_, None) => "".to_owned(),
+      Label::Value(_, Some(data)) => Ok(escape_label(data)),
+      Label::Value(_, None) => Ok("".to_owned()),
+    })
+    .collect::<Result<Vec<String>, ParseError>>()
+    .map(|labels| labels.join("."))
+}
+
+pub fn escape_label(label: &[u8]) -> String {
+  label.iter().fold(String::new(), |mut escaped, b| {
+    match b {
+      b'.' | b'\\' => {
+        escaped.push('\\');
+        escaped.push(*b as char);
+      }
+      0x21..=0x7e => escaped.push(*b as char),
+      _ => escaped.push_str(&format!("\\{:03}", b)),
+    }
+    escaped
+  })
+}
+
+pub(crate) fn split_label_bytes(name: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+  if name == "." {
+    return Ok(vec![]);
+  }
+
+  let mut labels = vec![];
+  let mut label = vec![];
+  let mut bytes = name.bytes();
+
+  while let Some(b) = bytes.next() {
+    match b {
+      b'.' => labels.push(std::mem::take(&mut label)),
+      b'\\' => match bytes.next() {
+        Some(d) if d.is_ascii_digit() => {
+          let digits = [Some(d), bytes.next(), bytes.next()];
+          let value = digits.iter().try_fold(0u16, |value, digit| match digit {
+            Some(digit) if digit.is_ascii_digit() => Some(value * 10 + (digit - b'0') as u16),
+            _ => None,
+          });
+          match value {
+            Some(value) if value <= 255 => label.push(value as u8),
+            _ => {
+              return Err(ParseError::NameError(
+                ErrorCode::InvalidName,
+                format!("Invalid decimal escape in name: {}", name),
+              ))
+            }
+          }
+        }
+        Some(escaped) => label.push(escaped),
+        None => {
+          return Err(ParseError::NameError(
+            ErrorCode::InvalidName,
+            format!("Name ends with an escape character: {}", name),
+          ))
+        }
+      },
+      b => label.push(b),
+    }
+  }
+
+  if !label.is_empty() {
+    labels.push(label);
+  }
+
+  if labels.iter().any(|l| l.is_empty()) {
+    return Err(ParseError::NameError(
+      ErrorCode::InvalidName,
+      format!("Empty label in name: {}", name),
+    ));
+  }
+
+  if labels.iter().any(|l| l.len() > 63) {
+    return Err(ParseError::NameError(
+      ErrorCode::LabelTooLong,
+      format!("Label exceeds limit of 63 in name: {}", name),
+    ));
+  }
+
+  Ok(labels)
+}
+
+pub fn split_labels(name: &str) -> Result<Vec<String>, ParseError> {
+  Ok(
+    split_label_bytes(name)?
+      .iter()
+      .map(|l| String::from_utf8_lossy(l).into_owned())
+      .collect(),
+  )
+}
+
+pub fn name_to_labels(offset: usize, name: &str) -> Result<Vec<Label>, ParseError> {
+  let mut current_offset = offset;
+  let mut labels = vec![];
+
+  for label in split_label_bytes(name)? {
+    let label = Label::Value(current_offset as u16, Some(label));
+    current_offset += label.size();
+    labels.push(label);
+  }
+  labels.push(Label::Value(current_offset as u16, None));
+
+  Ok(labels)
+}
+
+pub(crate) fn encoded_name_len(name: &str) -> usize {
+  split_label_bytes(name)
+    .map(|labels| labels.iter().fold(1, |sum, l| sum + l.len() + 1))
+    .unwrap_or(name.len() + 2)
+}
+
+pub fn encode_name(name: &str) -> Result<Vec<u8>, ParseError> {
+  let mut data = vec![];
+  for label in split_label_bytes(name)? {
+    data.push(label.len() as u8);
+    data.extend(label);
+  }
+  data.push(0);
+  Ok(data)
+}
+
+pub fn labels_to_name(labels: &[Label]) -> String {
+  labels
+    .iter()
+    .filter_map(|l| match l {
+      Label::Value(_, Some(data)) => Some(escape_label(data)),
+      _ => None,
     })
     .collect::<Vec<String>>()
     .join(".")
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Class {
-  Invalid,
   IN,
   CS,
   CH,
   HS,
+  Other(u16),
 }
 
+#[deprecated(note = "Use resource_record::RecordType instead")]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Type {
   Invalid,
@@ -117,16 +418,86 @@ pub enum Type {
   TXT,
 }
 
+impl Class {
+  pub fn code(&self) -> u16 {
+    match self {
+      Class::IN => 1,
+      Class::CS => 2,
+      Class::CH => 3,
+      Class::HS => 4,
+      Class::Other(n) => *n,
+    }
+  }
+}
+
+#[allow(deprecated)]
+impl Type {
+  pub fn code(&self) -> u16 {
+    match self {
+      Type::Invalid => 0,
+      Type::A => 1,
+      Type::NS => 2,
+      Type::MD => 3,
+      Type::MF => 4,
+      Type::CNAME => 5,
+      Type::SOA => 6,
+      Type::MB => 7,
+      Type::MG => 8,
+      Type::MR => 9,
+      Type::NULL => 10,
+      Type::WKS => 11,
+      Type::PTR => 12,
+      Type::HINFO => 13,
+      Type::MINFO => 14,
+      Type::MX => 15,
+      Type::TXT => 16,
+    }
+  }
+}
+
+impl std::fmt::Display for Class {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match class_mnemonic(self.code()) {
+      Some(mnemonic) => write!(f, "{}", mnemonic),
+      None => write!(f, "CLASS{}", self.code()),
+    }
+  }
+}
+
+impl std::str::FromStr for Class {
+  type Err = ParseError;
+
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    match class_code(name) {
+      Some(code) => Ok(parse_class(code.to_be_bytes())),
+      None => Err(ParseError::ResourceRecordError(
+        ErrorCode::InvalidPresentation,
+        format!("Unknown class: {}", name),
+      )),
+    }
+  }
+}
+
 pub fn parse_class(data: [u8; 2]) -> Class {
   match u16::from_be_bytes(data) {
     1 => Class::IN,
     2 => Class::CS,
     3 => Class::CH,
     4 => Class::HS,
-    _ => Class::Invalid,
+    n => Class::Other(n),
   }
 }
 
+pub fn parse_class_with_top_bit(data: [u8; 2], options: &ParseOptions) -> (Class, bool) {
+  let top_bit = data[0] & 0b10000000 == 0b10000000;
+  if top_bit && options.allow_class_top_bit {
+    return (parse_class([data[0] & 0b01111111, data[1]]), true);
+  }
+  (parse_class(data), false)
+}
+
+#[deprecated(note = "Use resource_record::RecordType instead")]
+#[allow(deprecated)]
 pub fn parse_type(data: [u8; 2]) -> Type {
   match u16::from_be_bytes(data) {
     1 => Type::A,
@@ -154,6 +525,7 @@ fn parse_label_value(offset: usize, data: &[u8]) -> Result<Label, ParseError> {
   let data_len = data.len();
   if data_len == 0 {
     return Err(ParseError::QueryLabelError(
+      ErrorCode::TruncatedName,
       "Data is zero length".to_owned(),
     ));
   }
@@ -164,12 +536,14 @@ fn parse_label_value(offset: usize, data: &[u8]) -> Result<Label, ParseError> {
 
   if count > 63 {
     return Err(ParseError::QueryLabelError(
+      ErrorCode::LabelTooLong,
       "Count exceeds limit of 63".to_owned(),
     ));
   }
 
   if (count as usize) > (data_len - 1) {
     return Err(ParseError::QueryLabelError(
+      ErrorCode::TruncatedName,
       "Wrong label count. Count would overflow data".to_owned(),
     ));
   }
@@ -178,6 +552,7 @@ fn parse_label_value(offset: usize, data: &[u8]) -> Result<Label, ParseError> {
   for &i in label_data {
     if i == 0 {
       return Err(ParseError::QueryLabelError(
+        ErrorCode::TruncatedName,
         "Zero encountered before end of label".to_owned(),
       ));
     }
@@ -186,20 +561,73 @@ fn parse_label_value(offset: usize, data: &[u8]) -> Result<Label, ParseError> {
   Ok(Label::Value(offset as u16, Some(Vec::from(label_data))))
 }
 
-pub fn parse_name(offset: usize, data: &[u8]) -> Result<Vec<Label>, ParseError> {
+fn check_name_limits(length: usize, count: usize, limits: &Limits) -> Result<(), ParseError> {
+  if length > limits.max_name_length {
+    return Err(ParseError::LimitError(
+      ErrorCode::LimitExceeded,
+      format!("Name length exceeds limit of {}", limits.max_name_length),
+    ));
+  }
+  if count > limits.max_labels {
+    return Err(ParseError::LimitError(
+      ErrorCode::LimitExceeded,
+      format!("Label count exceeds limit of {}", limits.max_labels),
+    ));
+  }
+  Ok(())
+}
+
+fn check_pointed_name_limits(
+  pointer: u16,
+  data: &[u8],
+  length: usize,
+  count: usize,
+  limits: &Limits,
+) -> Result<(), ParseError> {
+  let (mut offset, mut length, mut count) = (pointer as usize, length, count);
+  let mut visited_pointers = vec![pointer];
+
+  while let Some(&byte) = data.get(offset) {
+    if byte == 0 {
+      break;
+    }
+    match LABEL_TYPE_MASK & byte {
+      LABEL_MASK_TYPE_VALUE => {
+        length += byte as usize + 1;
+        count += 1;
+        check_name_limits(length, count, limits)?;
+        offset += byte as usize + 1;
+      }
+      LABEL_MASK_TYPE_POINTER => match parse_label_pointer(offset, data) {
+        Ok(Label::Pointer(_, target)) if !visited_pointers.contains(&target) => {
+          visited_pointers.push(target);
+          offset = target as usize;
+        }
+        _ => break,
+      },
+      _ => break,
+    }
+  }
+  Ok(())
+}
+
+pub fn parse_name(offset: usize, data: &[u8], limits: &Limits) -> Result<Vec<Label>, ParseError> {
   let mut values = vec![];
-  let mut index = 0;
   let mut current_offset = offset;
+  let mut length = 1;
+  let mut count = 0;
 
-  if data.len() == 0 {
+  if data.is_empty() {
     return Err(ParseError::QueryLabelError(
+      ErrorCode::TruncatedName,
       "Failed to parse query values, zero length data".to_owned(),
     ));
   }
 
   loop {
-    if data.len() <= index {
+    if data.len() <= current_offset {
       return Err(ParseError::QueryLabelError(
+        ErrorCode::TruncatedName,
         "Index going out of bounds when parsing query values".to_owned(),
       ));
     }
@@ -209,27 +637,33 @@ pub fn parse_name(offset: usize, data: &[u8]) -> Result<Vec<Label>, ParseError>
     let label = match label_type {
       LABEL_MASK_TYPE_POINTER => parse_label_pointer(current_offset, data),
       LABEL_MASK_TYPE_VALUE => parse_label_value(current_offset, data),
-      n => Err(ParseError::QueryLabelError(format!(
-        "Unknown label type: {}",
-        n
-      ))),
+      n => Err(ParseError::QueryLabelError(
+        ErrorCode::InvalidLabelType,
+        format!("Unknown label type: {}", n),
+      )),
     }?;
     current_offset += label.size();
     values.push(label.clone());
 
     match label {
-      Label::Pointer(_, _) => return Ok(values),
+      Label::Pointer(_, pointer) => {
+        check_pointed_name_limits(pointer, data, length, count, limits)?;
+        return Ok(values);
+      }
       Label::Value(_, None) => return Ok(values),
-      _ => {
-        index += label.size();
+      Label::Value(_, Some(bytes)) => {
+        length += bytes.len() + 1;
+        count += 1;
+        check_name_limits(length, count, limits)?;
       }
     }
   }
 }
 
 fn parse_label_pointer(offset: usize, data: &[u8]) -> Result<Label, ParseError> {
-  if data.len() < 2 {
+  if data.len() < offset + 2 {
     return Err(ParseError::QueryLabelError(
+      ErrorCode::TruncatedName,
       "Trying to parse pointer label, but data is not long enough".to_owned(),
     ));
   }
@@ -241,6 +675,7 @@ fn parse_label_pointer(offset: usize, data: &[u8]) -> Result<Label, ParseError>
 mod test {
 
   #[test]
+  #[allow(deprecated)]
   fn parse_type() {
     let test_data = [
       ([0, 0], super::Type::Invalid),
@@ -271,50 +706,59 @@ mod test {
 
   #[test]
   fn parse_name_label_with_zero_length() {
-    if let Ok(_) = super::parse_name(0, &[]) {
-      assert!(false);
-    }
+    assert!(super::parse_name(0, &[], &Default::default()).is_err());
   }
 
   #[test]
   fn parse_name_with_count_zero() {
-    let result = super::parse_name(0, &[0]);
+    let result = super::parse_name(0, &[0], &Default::default());
     assert_eq!(Ok(vec![super::Label::Value(0, None)]), result);
   }
 
   #[test]
   fn parse_name_with_overflowing_label_count() {
-    match super::parse_name(0, &[1]) {
-      Err(super::ParseError::QueryLabelError(_)) => {}
-      _ => {
-        assert!(false);
-      }
+    match super::parse_name(0, &[1], &Default::default()) {
+      Err(super::ParseError::QueryLabelError(..)) => {}
+      _ => panic!(),
     }
   }
 
   #[test]
   fn parse_name_with_label_higher_than_63_count() {
-    match super::parse_name(0, &[64]) {
-      Err(super::ParseError::QueryLabelError(_)) => {}
-      _ => {
-        assert!(false);
-      }
+    match super::parse_name(0, &[64], &Default::default()) {
+      Err(super::ParseError::QueryLabelError(..)) => {}
+      _ => panic!(),
     }
   }
 
   #[test]
   fn parse_name_with_premature_zero_in_label() {
-    match super::parse_name(0, &[4, 97, 98, 0, 99]) {
-      Err(super::ParseError::QueryLabelError(_)) => {}
-      _ => {
-        assert!(false);
+    match super::parse_name(0, &[4, 97, 98, 0, 99], &Default::default()) {
+      Err(super::ParseError::QueryLabelError(..)) => {}
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn parse_name_with_limits() {
+    let data = [1, 97, 1, 98, 0, 1, 99, 192, 0];
+    let limits = |max_labels, max_name_length| super::Limits {
+      max_labels,
+      max_name_length,
+      ..Default::default()
+    };
+    assert!(super::parse_name(5, &data, &limits(3, 7)).is_ok());
+    for exceeded in &[limits(2, 7), limits(3, 6)] {
+      match super::parse_name(5, &data, exceeded) {
+        Err(super::ParseError::LimitError(..)) => {}
+        _ => panic!(),
       }
     }
   }
 
   #[test]
   fn parse_name_with_label_text_abc() {
-    let result = super::parse_name(0, &[3, 97, 98, 99, 0]);
+    let result = super::parse_name(0, &[3, 97, 98, 99, 0], &Default::default());
     assert_eq!(
       Ok(vec![
         super::Label::Value(0, Some(vec![97, 98, 99])),
@@ -327,12 +771,13 @@ mod test {
   #[test]
   fn parse_class() {
     let test_data = [
-      ([0, 0], super::Class::Invalid),
+      ([0, 0], super::Class::Other(0)),
       ([0, 1], super::Class::IN),
       ([0, 2], super::Class::CS),
       ([0, 3], super::Class::CH),
       ([0, 4], super::Class::HS),
-      ([0, 5], super::Class::Invalid),
+      ([0, 5], super::Class::Other(5)),
+      ([0, 254], super::Class::Other(254)),
     ];
 
     for td in &test_data {
@@ -341,6 +786,33 @@ mod test {
     }
   }
 
+  #[test]
+  fn class_display_and_from_str() {
+    for name in &["IN", "HS", "CLASS32", "ANY", "NONE", "CLASS0"] {
+      let class = name.parse::<super::Class>().unwrap();
+      assert_eq!(*name, class.to_string());
+    }
+    assert_eq!(Ok(super::Class::Other(32)), "class32".parse());
+    assert!("BOGUS".parse::<super::Class>().is_err());
+  }
+
+  #[test]
+  fn parse_class_with_top_bit() {
+    let strict = super::ParseOptions::from(super::Profile::Strict);
+    let permissive = super::ParseOptions::from(super::Profile::PermissiveMdns);
+    let test_data = [
+      ([0, 1], &strict, (super::Class::IN, false)),
+      ([128, 1], &strict, (super::Class::Other(32769), false)),
+      ([0, 1], &permissive, (super::Class::IN, false)),
+      ([128, 1], &permissive, (super::Class::IN, true)),
+    ];
+
+    for td in &test_data {
+      let result = super::parse_class_with_top_bit(td.0, td.1);
+      assert_eq!(td.2, result);
+    }
+  }
+
   #[test]
   fn parse_label_pointer() {
     let data = [193, 10];
@@ -353,10 +825,8 @@ mod test {
     let data = [193];
     let result = super::parse_label_pointer(0, &data);
     match result {
-      Err(super::ParseError::QueryLabelError(_)) => {}
-      _ => {
-        assert!(false);
-      }
+      Err(super::ParseError::QueryLabelError(..)) => {}
+      _ => panic!(),
     }
   }
 
@@ -397,7 +867,7 @@ mod test {
   #[test]
   fn parse_name() {
     let data = &[3, 97, 98, 99, 2, 97, 98, 0, 4, 97, 98, 99, 100, 1, 97, 0];
-    let result = super::parse_name(0, data);
+    let result = super::parse_name(0, data, &Default::default());
     assert_eq!(
       Ok(vec![
         super::Label::Value(0, Some(vec![97, 98, 99])),
@@ -448,6 +918,152 @@ mod test {
     ];
 
     let domain_name = super::extract_domain_name(&all_labels, &all_labels[6..]);
-    assert_eq!("ab.cde.fgh.abc.def.ghi".to_owned(), domain_name);
+    assert_eq!(Ok("ab.cde.fgh.abc.def.ghi".to_owned()), domain_name);
+  }
+
+  #[test]
+  fn extract_domain_name_with_pointer_to_pointer() {
+    let all_labels = vec![
+      super::Label::Value(0, Some(vec![97, 98])),
+      super::Label::Value(3, None),
+      super::Label::Value(4, Some(vec![99])),
+      super::Label::Pointer(6, 0),
+      super::Label::Pointer(8, 4),
+    ];
+
+    let domain_name = super::extract_domain_name(&all_labels, &all_labels[4..]);
+    assert_eq!(Ok("c.ab".to_owned()), domain_name);
+  }
+
+  #[test]
+  fn extract_domain_name_with_pointer_loop() {
+    let all_labels = vec![super::Label::Pointer(0, 0)];
+
+    match super::extract_domain_name(&all_labels, &all_labels) {
+      Err(super::ParseError::QueryLabelError(..)) => {}
+      _ => panic!(),
+    }
+  }
+
+  #[test]
+  fn extract_domain_name_escapes_dots() {
+    let all_labels = vec![
+      super::Label::Value(0, Some(vec![97, 46, 98])),
+      super::Label::Value(4, Some(vec![99])),
+      super::Label::Value(6, None),
+    ];
+
+    let domain_name = super::extract_domain_name(&all_labels, &all_labels);
+    assert_eq!(Ok("a\\.b.c".to_owned()), domain_name);
+  }
+
+  #[test]
+  fn split_labels() {
+    let test_data = [
+      ("abc.def", vec!["abc", "def"]),
+      ("abc.def.", vec!["abc", "def"]),
+      ("a\\.b.c", vec!["a.b", "c"]),
+      ("a\\\\.b", vec!["a\\", "b"]),
+      ("a\\032b.c", vec!["a b", "c"]),
+      (".", vec![]),
+    ];
+
+    for td in &test_data {
+      let result = super::split_labels(td.0);
+      assert_eq!(
+        Ok(td.1.iter().map(|l| l.to_string()).collect::<Vec<String>>()),
+        result
+      );
+    }
+  }
+
+  #[test]
+  fn split_labels_and_fail() {
+    for name in &["a..b", "a\\", "a\\25", "a\\256", &"a".repeat(64)] {
+      match super::split_labels(name) {
+        Err(super::ParseError::NameError(..)) => {}
+        _ => panic!(),
+      }
+    }
+  }
+
+  #[test]
+  fn name_to_labels() {
+    let result = super::name_to_labels(12, "ab.c.");
+    assert_eq!(
+      Ok(vec![
+        super::Label::Value(12, Some(vec![97, 98])),
+        super::Label::Value(15, Some(vec![99])),
+        super::Label::Value(17, None),
+      ]),
+      result
+    );
+  }
+
+  #[test]
+  fn encode_name() {
+    let result = super::encode_name("ab.c\\.d");
+    assert_eq!(Ok(vec![2, 97, 98, 3, 99, 46, 100, 0]), result);
+  }
+
+  #[test]
+  fn labels_to_name() {
+    let labels = super::name_to_labels(0, "a\\.b.c").unwrap();
+    assert_eq!("a\\.b.c", super::labels_to_name(&labels));
+  }
+
+  #[test]
+  fn labels_to_name_with_binary_label() {
+    let labels = vec![
+      super::Label::Value(0, Some(vec![0xff, b' ', b'a', 0])),
+      super::Label::Value(5, Some(b"local".to_vec())),
+      super::Label::Value(11, None),
+    ];
+    let name = super::labels_to_name(&labels);
+    assert_eq!("\\255\\032a\\000.local", name);
+    assert_eq!(labels, super::name_to_labels(0, &name).unwrap());
+  }
+
+  #[test]
+  fn classify() {
+    assert_eq!(
+      "E_TRUNCATED_HEADER",
+      crate::header::parse_header(&[0]).unwrap_err().code()
+    );
+    assert_eq!(
+      super::ErrorCode::PointerLoop,
+      crate::message::parse(&[0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 192, 12, 0, 1, 0, 1])
+        .unwrap_err()
+        .classify()
+    );
+    assert_eq!(
+      super::ErrorCode::TrailingData,
+      crate::message::parse(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255])
+        .unwrap_err()
+        .classify()
+    );
+    assert_eq!(
+      super::ErrorCode::LabelTooLong,
+      super::encode_name(&"a".repeat(64)).unwrap_err().classify()
+    );
+    assert_eq!(
+      super::ErrorCode::InvalidPresentation,
+      "printer.local A BOGUS"
+        .parse::<crate::query::Query>()
+        .unwrap_err()
+        .classify()
+    );
+    assert_eq!(
+      super::ErrorCode::DataLengthMismatch,
+      crate::edns::parse_owner_option(&[0, 0, 1])
+        .unwrap_err()
+        .classify()
+    );
+    assert_eq!(
+      "E_INVALID_CONFIG",
+      crate::stamp::parse_stamp("https://example.com")
+        .unwrap_err()
+        .code()
+    );
   }
 }
@@ -0,0 +1,140 @@
+use crate::message::Message;
+use crate::resource_record::ResourceRecordData;
+use crate::service_instance::parse_service_instance_name;
+use crate::shared::{ErrorCode, ParseError};
+
+const SLEEP_PROXY_SERVICE_TYPE: &str = "_sleep-proxy._udp";
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SleepProxy {
+  pub proxy_type: u8,
+  pub portability: u8,
+  pub marginal_power: u8,
+  pub total_power: u8,
+  pub features: Option<u8>,
+  pub name: String,
+}
+
+fn parse_sleep_proxy_metric(value: &str, instance: &str) -> Result<u8, ParseError> {
+  value.parse::<u8>().map_err(|_| {
+    ParseError::NameError(
+      ErrorCode::InvalidName,
+      format!("Invalid sleep proxy metric in instance name: {}", instance),
+    )
+  })
+}
+
+pub fn parse_sleep_proxy_instance(instance: &str) -> Result<SleepProxy, ParseError> {
+  let (metrics, name) = match instance.find(' ') {
+    Some(index) => (&instance[..index], &instance[index + 1..]),
+    None => (instance, ""),
+  };
+
+  let (metrics, features) = match metrics.find('.') {
+    Some(index) => (
+      &metrics[..index],
+      Some(parse_sleep_proxy_metric(&metrics[index + 1..], instance)?),
+    ),
+    None => (metrics, None),
+  };
+
+  let metrics = metrics
+    .split('-')
+    .map(|m| parse_sleep_proxy_metric(m, instance))
+    .collect::<Result<Vec<u8>, ParseError>>()?;
+
+  if metrics.len() != 4 {
+    return Err(ParseError::NameError(
+      ErrorCode::InvalidName,
+      format!("Sleep proxy instance name needs four metrics: {}", instance),
+    ));
+  }
+
+  Ok(SleepProxy {
+    proxy_type: metrics[0],
+    portability: metrics[1],
+    marginal_power: metrics[2],
+    total_power: metrics[3],
+    features,
+    name: name.to_owned(),
+  })
+}
+
+pub fn is_sleep_proxy_service(name: &str) -> bool {
+  match parse_service_instance_name(name) {
+    Ok(service_instance_name) => service_instance_name
+      .service_type
+      .eq_ignore_ascii_case(SLEEP_PROXY_SERVICE_TYPE),
+    Err(_) => false,
+  }
+}
+
+pub fn sleep_proxies(message: &Message) -> Vec<SleepProxy> {
+  message
+    .answers
+    .iter()
+    .chain(message.additional_records.iter())
+    .filter_map(|r| match &r.resource_record_data {
+      ResourceRecordData::PTR(name) if is_sleep_proxy_service(name) => {
+        parse_service_instance_name(name).ok()
+      }
+      _ => None,
+    })
+    .filter_map(|n| parse_sleep_proxy_instance(&n.instance).ok())
+    .collect()
+}
+
+mod test {
+
+  #[test]
+  fn parse_sleep_proxy_instance() {
+    let result = super::parse_sleep_proxy_instance("70-35-60-63.1 Living Room");
+    assert_eq!(
+      Ok(super::SleepProxy {
+        proxy_type: 70,
+        portability: 35,
+        marginal_power: 60,
+        total_power: 63,
+        features: Some(1),
+        name: "Living Room".to_owned(),
+      }),
+      result
+    );
+  }
+
+  #[test]
+  fn parse_sleep_proxy_instance_without_features() {
+    let result = super::parse_sleep_proxy_instance("10-34-10-70 Apple TV");
+    assert_eq!(
+      Ok(super::SleepProxy {
+        proxy_type: 10,
+        portability: 34,
+        marginal_power: 10,
+        total_power: 70,
+        features: None,
+        name: "Apple TV".to_owned(),
+      }),
+      result
+    );
+  }
+
+  #[test]
+  fn parse_sleep_proxy_instance_and_fail() {
+    for instance in &["Living Room", "10-34-10 Apple TV", "10-34-10-700 Apple TV"] {
+      match super::parse_sleep_proxy_instance(instance) {
+        Err(super::ParseError::NameError(..)) => {}
+        _ => panic!(),
+      }
+    }
+  }
+
+  #[test]
+  fn is_sleep_proxy_service() {
+    assert!(super::is_sleep_proxy_service(
+      "70-35-60-63\\.1 Living Room._sleep-proxy._udp.local"
+    ));
+    assert!(!super::is_sleep_proxy_service(
+      "Living Room._googlecast._tcp.local"
+    ));
+  }
+}
@@ -0,0 +1,258 @@
+use crate::shared::{ErrorCode, ParseError};
+
+/*
+https://dnscrypt.info/stamps-specifications
+*/
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StampProtocol {
+  Plain,
+  DnsCrypt,
+  DoH,
+  DoT,
+  DoQ,
+  ODoHTarget,
+  DnsCryptRelay,
+  ODoHRelay,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StampProperties {
+  pub dnssec: bool,
+  pub no_logs: bool,
+  pub no_filter: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stamp {
+  pub protocol: StampProtocol,
+  pub properties: StampProperties,
+  pub address: String,
+  pub public_key: Vec<u8>,
+  pub provider_name: String,
+  pub hashes: Vec<Vec<u8>>,
+  pub hostname: String,
+  pub path: String,
+  pub bootstrap: Vec<String>,
+}
+
+fn stamp_error(message: &str) -> ParseError {
+  ParseError::ConfigError(
+    ErrorCode::InvalidConfig,
+    format!("Invalid DNS stamp: {}", message),
+  )
+}
+
+fn decode_base64_url(value: &str) -> Result<Vec<u8>, ParseError> {
+  let mut bytes = vec![];
+  let mut buffer = 0u32;
+  let mut bits = 0;
+
+  for c in value.trim_end_matches('=').bytes() {
+    let sextet = match c {
+      b'A'..=b'Z' => c - b'A',
+      b'a'..=b'z' => c - b'a' + 26,
+      b'0'..=b'9' => c - b'0' + 52,
+      b'-' => 62,
+      b'_' => 63,
+      _ => return Err(stamp_error("invalid base64 character")),
+    };
+    buffer = buffer << 6 | sextet as u32;
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      bytes.push((buffer >> bits) as u8);
+      buffer &= (1 << bits) - 1;
+    }
+  }
+
+  Ok(bytes)
+}
+
+struct StampReader<'a> {
+  data: &'a [u8],
+  offset: usize,
+}
+
+impl<'a> StampReader<'a> {
+  fn is_empty(&self) -> bool {
+    self.offset >= self.data.len()
+  }
+
+  fn bytes(&mut self, length: usize) -> Result<&'a [u8], ParseError> {
+    if self.data.len() < self.offset + length {
+      return Err(stamp_error("data not long enough"));
+    }
+    let bytes = &self.data[self.offset..self.offset + length];
+    self.offset += length;
+    Ok(bytes)
+  }
+
+  fn properties(&mut self) -> Result<StampProperties, ParseError> {
+    let bytes = self.bytes(8)?;
+    Ok(StampProperties {
+      dnssec: bytes[0] & 1 != 0,
+      no_logs: bytes[0] & 2 != 0,
+      no_filter: bytes[0] & 4 != 0,
+    })
+  }
+
+  fn lp(&mut self) -> Result<Vec<u8>, ParseError> {
+    let length = self.bytes(1)?[0] as usize;
+    Ok(self.bytes(length)?.to_vec())
+  }
+
+  fn lp_string(&mut self) -> Result<String, ParseError> {
+    String::from_utf8(self.lp()?).map_err(|_| stamp_error("string is not valid UTF-8"))
+  }
+
+  fn vlp(&mut self) -> Result<Vec<Vec<u8>>, ParseError> {
+    let mut items = vec![];
+    loop {
+      let length = self.bytes(1)?[0];
+      items.push(self.bytes((length & 0x7f) as usize)?.to_vec());
+      if length & 0x80 == 0 {
+        break;
+      }
+    }
+    Ok(items.into_iter().filter(|i| !i.is_empty()).collect())
+  }
+
+  fn bootstrap(&mut self) -> Result<Vec<String>, ParseError> {
+    if self.is_empty() {
+      return Ok(vec![]);
+    }
+    self
+      .vlp()?
+      .into_iter()
+      .map(|i| String::from_utf8(i).map_err(|_| stamp_error("string is not valid UTF-8")))
+      .collect()
+  }
+}
+
+pub fn parse_stamp(value: &str) -> Result<Stamp, ParseError> {
+  let data = match value.strip_prefix("sdns://") {
+    Some(encoded) => decode_base64_url(encoded)?,
+    None => return Err(stamp_error("missing sdns:// prefix")),
+  };
+  let mut reader = StampReader {
+    data: &data,
+    offset: 0,
+  };
+  let mut stamp = Stamp {
+    protocol: StampProtocol::Plain,
+    properties: StampProperties::default(),
+    address: String::new(),
+    public_key: vec![],
+    provider_name: String::new(),
+    hashes: vec![],
+    hostname: String::new(),
+    path: String::new(),
+    bootstrap: vec![],
+  };
+
+  match reader.bytes(1)?[0] {
+    0x00 => {
+      stamp.properties = reader.properties()?;
+      stamp.address = reader.lp_string()?;
+    }
+    0x01 => {
+      stamp.protocol = StampProtocol::DnsCrypt;
+      stamp.properties = reader.properties()?;
+      stamp.address = reader.lp_string()?;
+      stamp.public_key = reader.lp()?;
+      stamp.provider_name = reader.lp_string()?;
+    }
+    protocol @ 0x02 | protocol @ 0x85 => {
+      stamp.protocol = match protocol {
+        0x02 => StampProtocol::DoH,
+        _ => StampProtocol::ODoHRelay,
+      };
+      stamp.properties = reader.properties()?;
+      stamp.address = reader.lp_string()?;
+      stamp.hashes = reader.vlp()?;
+      stamp.hostname = reader.lp_string()?;
+      stamp.path = reader.lp_string()?;
+      stamp.bootstrap = reader.bootstrap()?;
+    }
+    protocol @ 0x03 | protocol @ 0x04 => {
+      stamp.protocol = match protocol {
+        0x03 => StampProtocol::DoT,
+        _ => StampProtocol::DoQ,
+      };
+      stamp.properties = reader.properties()?;
+      stamp.address = reader.lp_string()?;
+      stamp.hashes = reader.vlp()?;
+      stamp.hostname = reader.lp_string()?;
+      stamp.bootstrap = reader.bootstrap()?;
+    }
+    0x05 => {
+      stamp.protocol = StampProtocol::ODoHTarget;
+      stamp.properties = reader.properties()?;
+      stamp.hostname = reader.lp_string()?;
+      stamp.path = reader.lp_string()?;
+    }
+    0x81 => {
+      stamp.protocol = StampProtocol::DnsCryptRelay;
+      stamp.address = reader.lp_string()?;
+    }
+    n => return Err(stamp_error(&format!("unknown protocol {}", n))),
+  }
+
+  if !reader.is_empty() {
+    return Err(stamp_error("trailing data"));
+  }
+
+  Ok(stamp)
+}
+
+mod test {
+
+  #[test]
+  fn parse_stamp_for_doh() {
+    let stamp =
+      super::parse_stamp("sdns://AgcAAAAAAAAABzEuMC4wLjEAEmRucy5jbG91ZGZsYXJlLmNvbQovZG5zLXF1ZXJ5")
+        .unwrap();
+    assert_eq!(super::StampProtocol::DoH, stamp.protocol);
+    assert_eq!(
+      super::StampProperties {
+        dnssec: true,
+        no_logs: true,
+        no_filter: true
+      },
+      stamp.properties
+    );
+    assert_eq!(
+      ("1.0.0.1", "dns.cloudflare.com", "/dns-query"),
+      (
+        stamp.address.as_str(),
+        stamp.hostname.as_str(),
+        stamp.path.as_str()
+      )
+    );
+    assert!(stamp.hashes.is_empty());
+  }
+
+  #[test]
+  fn parse_stamp_for_plain_and_dot() {
+    let plain = super::parse_stamp("sdns://AAEAAAAAAAAACjkuOS45Ljk6NTM").unwrap();
+    assert_eq!(super::StampProtocol::Plain, plain.protocol);
+    assert_eq!("9.9.9.9:53", plain.address);
+    assert!(plain.properties.dnssec);
+
+    let dot = super::parse_stamp("sdns://AwIAAAAAAAAAAIIBAgEDB2RvdC5sYW4").unwrap();
+    assert_eq!(super::StampProtocol::DoT, dot.protocol);
+    assert_eq!(vec![vec![1, 2], vec![3]], dot.hashes);
+    assert_eq!("dot.lan", dot.hostname);
+  }
+
+  #[test]
+  fn parse_stamp_and_fail() {
+    for value in &["https://example.com", "sdns://AgcA", "sdns://!!"] {
+      match super::parse_stamp(value) {
+        Err(super::ParseError::ConfigError(..)) => {}
+        _ => panic!(),
+      }
+    }
+  }
+}
@@ -0,0 +1,27 @@
+use crate::header::parse_header;
+use crate::message::Message;
+use crate::resource_record::{parse_resource_record_line, ResourceRecord};
+
+pub fn record(line: &str) -> ResourceRecord {
+  parse_resource_record_line(line).unwrap()
+}
+
+pub fn message(
+  flags: [u8; 2],
+  answers: &[&str],
+  name_servers: &[&str],
+  additional_records: &[&str],
+) -> Message {
+  Message {
+    header: parse_header(&[0, 0, flags[0], flags[1], 0, 0, 0, 0, 0, 0, 0, 0]).unwrap(),
+    queries: vec![],
+    answers: answers.iter().map(|l| record(l)).collect(),
+    name_servers: name_servers.iter().map(|l| record(l)).collect(),
+    additional_records: additional_records.iter().map(|l| record(l)).collect(),
+    raw: None,
+  }
+}
+
+pub fn response(lines: &[&str]) -> Message {
+  message([132, 0], lines, &[], &[])
+}
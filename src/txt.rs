@@ -0,0 +1,376 @@
+use crate::edns::{parse_hardware_address, HardwareAddress};
+use crate::shared::{ErrorCode, ParseError};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Googlecast {
+  /// `id`: device identifier.
+  pub id: Option<String>,
+  /// `md`: model name, e.g. "Google Home Mini".
+  pub model: Option<String>,
+  /// `fn`: user-assigned friendly name.
+  pub friendly_name: Option<String>,
+  /// `ve`: protocol version.
+  pub version: Option<String>,
+  /// `ic`: path of the device icon on the device's web server.
+  pub icon_path: Option<String>,
+  /// `ca`: capability bitmask.
+  pub capabilities: Option<u32>,
+  /// `st`: 0 when idle, 1 when casting.
+  pub status: Option<u8>,
+  /// `rs`: text describing what is currently casting.
+  pub receiver_status: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AirPlay {
+  /// `deviceid`: MAC-style device identifier (AirPlay only).
+  pub device_id: Option<String>,
+  /// `model` or `am`: hardware model, e.g. "AppleTV5,3".
+  pub model: Option<String>,
+  /// `features` or `ft`: 64-bit feature bitmask, sent as "low,high" 32-bit halves.
+  pub features: Option<u64>,
+  /// `flags` or `sf`: status flags.
+  pub flags: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct HomeKit {
+  /// `id`: device identifier, stable until the accessory is reset.
+  pub id: Option<String>,
+  /// `md`: model name.
+  pub model: Option<String>,
+  /// `c#`: configuration number, incremented when the accessory database changes.
+  pub configuration_number: Option<u32>,
+  /// `s#`: state number.
+  pub state_number: Option<u32>,
+  /// `ci`: accessory category identifier.
+  pub category: Option<u16>,
+  /// `ff`: pairing feature flags.
+  pub feature_flags: Option<u8>,
+  /// `sf`: status flags, bit 0 set while the accessory is unpaired.
+  pub status_flags: Option<u8>,
+  /// `pv`: protocol version.
+  pub protocol_version: Option<String>,
+}
+
+/// Value of a TXT attribute. Values that are not valid UTF-8 are kept as raw bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TxtValue {
+  Text(String),
+  Binary(Vec<u8>),
+}
+
+impl TxtValue {
+  fn new(bytes: &[u8]) -> TxtValue {
+    match std::str::from_utf8(bytes) {
+      Ok(text) => TxtValue::Text(text.to_owned()),
+      Err(_) => TxtValue::Binary(bytes.to_vec()),
+    }
+  }
+
+  pub fn as_text(&self) -> Option<&str> {
+    match self {
+      TxtValue::Text(text) => Some(text),
+      TxtValue::Binary(_) => None,
+    }
+  }
+}
+
+impl std::fmt::Display for TxtValue {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      TxtValue::Text(text) => write!(f, "{}", text),
+      TxtValue::Binary(bytes) => bytes.iter().try_for_each(|b| match b {
+        b'\\' => write!(f, "\\\\"),
+        0x21..=0x7e => write!(f, "{}", *b as char),
+        _ => write!(f, "\\{:03}", b),
+      }),
+    }
+  }
+}
+
+pub type TxtAttribute = (String, Option<TxtValue>);
+
+pub fn parse_txt_attributes(strings: &[Vec<u8>]) -> Vec<TxtAttribute> {
+  strings
+    .iter()
+    .filter(|s| !s.is_empty() && s[0] != b'=')
+    .filter_map(|s| {
+      let (key, value) = match s.iter().position(|b| *b == b'=') {
+        Some(index) => (&s[..index], Some(TxtValue::new(&s[index + 1..]))),
+        None => (&s[..], None),
+      };
+      std::str::from_utf8(key)
+        .ok()
+        .map(|key| (key.to_owned(), value))
+    })
+    .fold(vec![], |mut attributes: Vec<TxtAttribute>, attribute| {
+      if !attributes
+        .iter()
+        .any(|(key, _)| key.eq_ignore_ascii_case(&attribute.0))
+      {
+        attributes.push(attribute);
+      }
+      attributes
+    })
+}
+
+fn txt_value(attributes: &[TxtAttribute], keys: &[&str]) -> Option<String> {
+  attributes
+    .iter()
+    .find(|(key, _)| keys.iter().any(|k| key.eq_ignore_ascii_case(k)))
+    .and_then(|(_, value)| value.as_ref().and_then(|v| v.as_text()))
+    .map(|text| text.to_owned())
+}
+
+fn parse_txt_number(key: &str, value: &str) -> Result<u64, ParseError> {
+  let result = if value.starts_with("0x") || value.starts_with("0X") {
+    u64::from_str_radix(&value[2..], 16)
+  } else {
+    value.parse::<u64>()
+  };
+
+  result.map_err(|_| {
+    ParseError::TxtError(
+      ErrorCode::InvalidTxt,
+      format!("Invalid number for {}: {}", key, value),
+    )
+  })
+}
+
+fn txt_number<T: std::convert::TryFrom<u64>>(
+  attributes: &[TxtAttribute],
+  keys: &[&str],
+) -> Result<Option<T>, ParseError> {
+  match txt_value(attributes, keys) {
+    Some(value) => {
+      let number = parse_txt_number(keys[0], &value)?;
+      T::try_from(number).map(Some).map_err(|_| {
+        ParseError::TxtError(
+          ErrorCode::InvalidTxt,
+          format!("Number out of range for {}: {}", keys[0], value),
+        )
+      })
+    }
+    None => Ok(None),
+  }
+}
+
+fn parse_airplay_features(value: &str) -> Result<u64, ParseError> {
+  let mut halves = value.split(',');
+  let low = parse_txt_number("features", halves.next().unwrap_or(""))?;
+  let high = match halves.next() {
+    Some(high) => parse_txt_number("features", high)?,
+    None => 0,
+  };
+
+  if low > u32::MAX as u64 || high > u32::MAX as u64 || halves.next().is_some() {
+    return Err(ParseError::TxtError(
+      ErrorCode::InvalidTxt,
+      format!("Invalid AirPlay features: {}", value),
+    ));
+  }
+
+  Ok(high << 32 | low)
+}
+
+pub fn decode_googlecast(strings: &[Vec<u8>]) -> Result<Googlecast, ParseError> {
+  let attributes = parse_txt_attributes(strings);
+  Ok(Googlecast {
+    id: txt_value(&attributes, &["id"]),
+    model: txt_value(&attributes, &["md"]),
+    friendly_name: txt_value(&attributes, &["fn"]),
+    version: txt_value(&attributes, &["ve"]),
+    icon_path: txt_value(&attributes, &["ic"]),
+    capabilities: txt_number(&attributes, &["ca"])?,
+    status: txt_number(&attributes, &["st"])?,
+    receiver_status: txt_value(&attributes, &["rs"]),
+  })
+}
+
+pub fn decode_airplay(strings: &[Vec<u8>]) -> Result<AirPlay, ParseError> {
+  let attributes = parse_txt_attributes(strings);
+  Ok(AirPlay {
+    device_id: txt_value(&attributes, &["deviceid"]),
+    model: txt_value(&attributes, &["model", "am"]),
+    features: match txt_value(&attributes, &["features", "ft"]) {
+      Some(value) => Some(parse_airplay_features(&value)?),
+      None => None,
+    },
+    flags: txt_number(&attributes, &["flags", "sf"])?,
+  })
+}
+
+pub fn decode_homekit(strings: &[Vec<u8>]) -> Result<HomeKit, ParseError> {
+  let attributes = parse_txt_attributes(strings);
+  Ok(HomeKit {
+    id: txt_value(&attributes, &["id"]),
+    model: txt_value(&attributes, &["md"]),
+    configuration_number: txt_number(&attributes, &["c#"])?,
+    state_number: txt_number(&attributes, &["s#"])?,
+    category: txt_number(&attributes, &["ci"])?,
+    feature_flags: txt_number(&attributes, &["ff"])?,
+    status_flags: txt_number(&attributes, &["sf"])?,
+    protocol_version: txt_value(&attributes, &["pv"]),
+  })
+}
+
+pub fn txt_hardware_address(attributes: &[TxtAttribute]) -> Option<HardwareAddress> {
+  txt_value(attributes, &["rpBA", "deviceid"]).and_then(|value| parse_hardware_address(&value))
+}
+
+mod test {
+
+  #[allow(dead_code)]
+  fn to_strings(strings: &[&str]) -> Vec<Vec<u8>> {
+    strings.iter().map(|s| s.as_bytes().to_vec()).collect()
+  }
+
+  #[test]
+  fn parse_txt_attributes() {
+    let strings = to_strings(&["a=1", "flag", "", "=x", "A=2", "b="]);
+    let result = super::parse_txt_attributes(&strings);
+    assert_eq!(
+      vec![
+        ("a".to_owned(), Some(super::TxtValue::Text("1".to_owned()))),
+        ("flag".to_owned(), None),
+        ("b".to_owned(), Some(super::TxtValue::Text("".to_owned()))),
+      ],
+      result
+    );
+  }
+
+  #[test]
+  fn parse_txt_attributes_with_binary_value() {
+    let strings = vec![b"pk=\xff\x00a".to_vec(), b"\xff=1".to_vec()];
+    let result = super::parse_txt_attributes(&strings);
+    assert_eq!(
+      vec![(
+        "pk".to_owned(),
+        Some(super::TxtValue::Binary(vec![0xff, 0, b'a']))
+      )],
+      result
+    );
+    assert_eq!("\\255\\000a", result[0].1.as_ref().unwrap().to_string());
+  }
+
+  #[test]
+  fn decode_googlecast_with_binary_value() {
+    let result = super::decode_googlecast(&[b"fn=\xff".to_vec()]).unwrap();
+    assert_eq!(None, result.friendly_name);
+  }
+
+  #[test]
+  fn decode_googlecast() {
+    let strings = to_strings(&[
+      "id=e0719ee5d7f89bfd9ea7445a71005752",
+      "cd=E0054E250D6CD14878C93CC1F7AC647D",
+      "rm=41772A7B8863FB0E",
+      "ve=05",
+      "md=Google Home Mini",
+      "ic=/setup/icon.png",
+      "fn=Living Room speaker",
+      "ca=198660",
+      "st=0",
+      "bs=FA8FCA9DBCEF",
+      "nf=1",
+      "rs=",
+    ]);
+    let result = super::decode_googlecast(&strings);
+    assert_eq!(
+      Ok(super::Googlecast {
+        id: Some("e0719ee5d7f89bfd9ea7445a71005752".to_owned()),
+        model: Some("Google Home Mini".to_owned()),
+        friendly_name: Some("Living Room speaker".to_owned()),
+        version: Some("05".to_owned()),
+        icon_path: Some("/setup/icon.png".to_owned()),
+        capabilities: Some(198660),
+        status: Some(0),
+        receiver_status: Some("".to_owned()),
+      }),
+      result
+    );
+  }
+
+  #[test]
+  fn decode_airplay() {
+    let strings = to_strings(&[
+      "deviceid=58:55:CA:1A:E2:88",
+      "features=0x5A7FFFF7,0x1E",
+      "flags=0x4",
+      "model=AppleTV5,3",
+    ]);
+    let result = super::decode_airplay(&strings);
+    assert_eq!(
+      Ok(super::AirPlay {
+        device_id: Some("58:55:CA:1A:E2:88".to_owned()),
+        model: Some("AppleTV5,3".to_owned()),
+        features: Some(0x1E_5A7F_FFF7),
+        flags: Some(4),
+      }),
+      result
+    );
+  }
+
+  #[test]
+  fn decode_raop() {
+    let strings = to_strings(&["am=AppleTV3,2", "ft=0x5A7FFFF7", "sf=0x44"]);
+    let result = super::decode_airplay(&strings);
+    assert_eq!(
+      Ok(super::AirPlay {
+        device_id: None,
+        model: Some("AppleTV3,2".to_owned()),
+        features: Some(0x5A7F_FFF7),
+        flags: Some(0x44),
+      }),
+      result
+    );
+  }
+
+  #[test]
+  fn decode_homekit() {
+    let strings = to_strings(&[
+      "c#=2",
+      "ff=1",
+      "id=AB:CD:EF:12:34:56",
+      "md=Bridge",
+      "pv=1.1",
+      "s#=1",
+      "sf=1",
+      "ci=2",
+    ]);
+    let result = super::decode_homekit(&strings);
+    assert_eq!(
+      Ok(super::HomeKit {
+        id: Some("AB:CD:EF:12:34:56".to_owned()),
+        model: Some("Bridge".to_owned()),
+        configuration_number: Some(2),
+        state_number: Some(1),
+        category: Some(2),
+        feature_flags: Some(1),
+        status_flags: Some(1),
+        protocol_version: Some("1.1".to_owned()),
+      }),
+      result
+    );
+  }
+
+  #[test]
+  fn decode_homekit_and_fail() {
+    for strings in &[to_strings(&["c#=abc"]), to_strings(&["ff=256"])] {
+      match super::decode_homekit(strings) {
+        Err(super::ParseError::TxtError(..)) => {}
+        _ => panic!(),
+      }
+    }
+  }
+
+  #[test]
+  fn txt_hardware_address() {
+    let attributes = super::parse_txt_attributes(&to_strings(&["rpBA=E2:4C:7B:30:11:A9"]));
+    assert_eq!(
+      Some([0xe2, 0x4c, 0x7b, 0x30, 0x11, 0xa9]),
+      super::txt_hardware_address(&attributes)
+    );
+  }
+}
@@ -0,0 +1,63 @@
+use dns_parser::message::parse_with_options;
+use dns_parser::shared::{ParseOptions, Profile};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn corpus_dir() -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("corpus")
+}
+
+fn corpus_files() -> Vec<PathBuf> {
+  let mut files = fs::read_dir(corpus_dir())
+    .unwrap()
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.extension().map(|e| e == "bin").unwrap_or(false))
+    .collect::<Vec<PathBuf>>();
+  files.sort();
+  files
+}
+
+fn snapshot(data: &[u8]) -> String {
+  [Profile::Strict, Profile::PermissiveMdns]
+    .iter()
+    .map(|profile| {
+      let options = ParseOptions::from(*profile);
+      format!(
+        "{:?}:\n{:#?}\n",
+        profile,
+        parse_with_options(data, &options)
+      )
+    })
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+#[test]
+fn corpus_matches_snapshots() {
+  let update = std::env::var("UPDATE_SNAPSHOTS").is_ok();
+  let mut mismatches = vec![];
+
+  for file in corpus_files() {
+    let actual = snapshot(&fs::read(&file).unwrap());
+    let snapshot_file = file.with_extension("snap");
+
+    if update {
+      fs::write(&snapshot_file, &actual).unwrap();
+      continue;
+    }
+
+    match fs::read_to_string(&snapshot_file) {
+      Ok(expected) if expected == actual => {}
+      Ok(_) => mismatches.push(format!("{} differs from snapshot", file.display())),
+      Err(_) => mismatches.push(format!("{} has no snapshot", file.display())),
+    }
+  }
+
+  assert!(
+    mismatches.is_empty(),
+    "{}\nRun with UPDATE_SNAPSHOTS=1 to accept the new output",
+    mismatches.join("\n")
+  );
+}